@@ -0,0 +1,132 @@
+//! Compares Day6 part 2 against the original brute force, which put an obstacle on every cell
+//! of a freshly parsed map and walked the guard from the start each time. The brute force here
+//! skips the guard's start, which the original wrongly tried as well.
+//!
+//!     cargo bench --bench day6
+
+use advent_of_code_2024::common::answer::Answer;
+use advent_of_code_2024::common::bench::{self, BenchOptions};
+use advent_of_code_2024::common::day::Day;
+use advent_of_code_2024::common::error::AocError;
+use advent_of_code_2024::common::pool;
+use advent_of_code_2024::common::registry::DayEntry;
+use advent_of_code_2024::days::day6::Day6;
+use std::collections::HashSet;
+
+const SIZE: usize = 130;
+
+struct BruteForce {
+    input: String,
+}
+
+const BRUTE_FORCE: DayEntry = DayEntry {
+    number: 6,
+    title: "Guard Gallivant (brute force)",
+    create: |input| Box::new(BruteForce { input }),
+};
+
+/// The real Day6 with the loop obstacle search spread over every thread
+const ALL_THREADS: DayEntry = DayEntry {
+    number: 6,
+    title: "Guard Gallivant",
+    create: |input| {
+        Box::new(Day6 {
+            input,
+            jobs: pool::available_jobs(),
+        })
+    },
+};
+
+impl Day for BruteForce {
+    fn part1(&self) -> Result<Answer, AocError> {
+        Day6 {
+            input: self.input.clone(),
+            jobs: 1,
+        }
+        .part1()
+    }
+
+    fn part2(&self) -> Result<Answer, AocError> {
+        const DELTAS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+        let mut loops = 0;
+        for index in 0..SIZE * SIZE {
+            let mut map = self
+                .input
+                .lines()
+                .map(|l| l.as_bytes().to_vec())
+                .collect::<Vec<_>>();
+            let (x, y) = (index % SIZE, index / SIZE);
+            if map[y][x] == b'^' {
+                continue;
+            }
+            map[y][x] = b'#';
+            let start = self.input.find('^').unwrap();
+            let (mut x, mut y, mut dir) =
+                ((start % (SIZE + 1)) as i32, (start / (SIZE + 1)) as i32, 0);
+            let mut seen = HashSet::from([(x, y, dir)]);
+            loop {
+                let (nx, ny) = (x + DELTAS[dir].0, y + DELTAS[dir].1);
+                match map.get(ny as usize).and_then(|row| row.get(nx as usize)) {
+                    None => break,
+                    Some(b'#') => dir = (dir + 1) % 4,
+                    Some(_) => (x, y) = (nx, ny),
+                }
+                if !seen.insert((x, y, dir)) {
+                    loops += 1;
+                    break;
+                }
+            }
+        }
+        Ok(loops.into())
+    }
+}
+
+/// A puzzle sized map with about one obstacle in twelve cells and the guard in the middle
+fn generate_map() -> String {
+    let mut seed: u64 = 2024;
+    let mut map = String::new();
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            map.push(match (x, y) {
+                _ if (x, y) == (SIZE / 2, SIZE / 2) => '^',
+                _ if (seed >> 33).is_multiple_of(12) => '#',
+                _ => '.',
+            });
+        }
+        map.push('\n');
+    }
+    map
+}
+
+fn main() -> Result<(), AocError> {
+    let input = generate_map();
+    let fast = Day6 {
+        input: input.clone(),
+        jobs: pool::available_jobs(),
+    };
+    let slow = BruteForce {
+        input: input.clone(),
+    };
+    let answer = fast.part2()?;
+    assert_eq!(answer, slow.part2()?, "both searches have to agree");
+    println!("{} loop obstacles on a {}x{} map", answer, SIZE, SIZE);
+
+    let options = BenchOptions {
+        iterations: 5,
+        warmup: 1,
+    };
+    let before = bench::bench_day(&BRUTE_FORCE, input.clone(), &options)?;
+    let after = bench::bench_day(&ALL_THREADS, input, &options)?;
+    println!("Brute force:");
+    before.print();
+    println!("Route candidates with jump tables, on all threads:");
+    after.print();
+    println!(
+        "Part 2 speed-up: {:.1}x",
+        before.part2.median.as_secs_f64() / after.part2.median.as_secs_f64()
+    );
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use crate::common::json;
+use std::fmt::{Display, Formatter};
+
+/// The answer of a single part. Numbers are kept as numbers, so callers don't have to parse
+/// them back out of a string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i64),
+    BigInteger(i128),
+    Text(String),
+    /// Multi-line answers, e.g. letters drawn on a grid, one entry per row
+    Grid(Vec<String>),
+}
+
+impl Answer {
+    /// Compares against an expected answer as stored in the answers file.
+    /// Surrounding whitespace, and trailing whitespace of grid rows, is ignored.
+    pub fn matches(&self, expected: &str) -> bool {
+        match self {
+            Answer::Grid(rows) => {
+                let expected = expected.trim_matches('\n').lines().map(|l| l.trim_end());
+                rows.iter().map(|r| r.trim_end()).eq(expected)
+            }
+            answer => answer.to_string().trim() == expected.trim(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        match self {
+            Answer::Integer(value) => format!("{{\"kind\":\"integer\",\"value\":{}}}", value),
+            // Kept as a string, JSON consumers tend to lose precision beyond 2^53
+            Answer::BigInteger(value) => format!(
+                "{{\"kind\":\"big_integer\",\"value\":{}}}",
+                json::quote(&value.to_string())
+            ),
+            Answer::Text(value) => {
+                format!("{{\"kind\":\"text\",\"value\":{}}}", json::quote(value))
+            }
+            Answer::Grid(rows) => format!(
+                "{{\"kind\":\"grid\",\"value\":[{}]}}",
+                rows.iter()
+                    .map(|row| json::quote(row))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{}", value),
+            Answer::BigInteger(value) => write!(f, "{}", value),
+            Answer::Text(value) => write!(f, "{}", value),
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+        }
+    }
+}
+
+macro_rules! integer_answer {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(value: $t) -> Self {
+                Answer::Integer(value.into())
+            }
+        })*
+    };
+}
+
+integer_answer!(i8, i16, i32, i64, u8, u16, u32);
+
+macro_rules! big_integer_answer {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(value: $t) -> Self {
+                match i64::try_from(value) {
+                    Ok(value) => Answer::Integer(value),
+                    Err(_) => Answer::BigInteger(value as i128),
+                }
+            }
+        })*
+    };
+}
+
+big_integer_answer!(u64, usize, i128);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Answer::from(41usize), Answer::Integer(41));
+        assert_eq!(Answer::from(u64::MAX), Answer::BigInteger(u64::MAX as i128));
+        assert_eq!(Answer::from("123EG"), Answer::Text("123EG".to_string()));
+    }
+
+    #[test]
+    fn matches_expected() {
+        assert!(Answer::Integer(41).matches(" 41\n"));
+        assert!(!Answer::Integer(41).matches("42"));
+        let grid = Answer::Grid(vec!["#..# ".to_string(), ".##.".to_string()]);
+        assert!(grid.matches("#..#\n.##.\n"));
+        assert!(!grid.matches("#..#"));
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            Answer::Integer(6).to_json(),
+            "{\"kind\":\"integer\",\"value\":6}"
+        );
+        assert_eq!(
+            Answer::Grid(vec!["a\"".to_string(), "b".to_string()]).to_json(),
+            "{\"kind\":\"grid\",\"value\":[\"a\\\"\",\"b\"]}"
+        );
+    }
+}
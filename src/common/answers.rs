@@ -0,0 +1,148 @@
+use crate::common::json;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// Expected answers per day and part, stored as a small TOML file next to the inputs:
+///
+/// ```toml
+/// [day6]
+/// part1 = "41"
+/// part2 = "6"
+/// ```
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers {
+    expected: BTreeMap<(u32, u8), String>,
+}
+
+impl Answers {
+    /// Loads the answers file, a missing file simply means nothing is known yet
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        if !path.is_file() {
+            return Ok(Answers::default());
+        }
+        Answers::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(path, self.to_toml())?;
+        Ok(())
+    }
+
+    pub fn parse(input: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut expected = BTreeMap::new();
+        let mut day = None;
+        for (i, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |reason: &str| format!("{} line {}: {}", ANSWERS_FILE, i + 1, reason);
+            if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let number = section
+                    .trim()
+                    .strip_prefix("day")
+                    .and_then(|n| n.parse::<u32>().ok())
+                    .ok_or_else(|| error("expected a section like [day6]"))?;
+                day = Some(number);
+                continue;
+            }
+            let day = day.ok_or_else(|| error("answer outside of a [dayN] section"))?;
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error("expected part1 = \"...\""))?;
+            let part = key
+                .trim()
+                .strip_prefix("part")
+                .and_then(|p| p.parse::<u8>().ok())
+                .ok_or_else(|| error("expected part1 or part2 as key"))?;
+            let value = unquote(value.trim()).ok_or_else(|| error("expected a quoted string"))?;
+            expected.insert((day, part), value);
+        }
+        Ok(Answers { expected })
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut toml = String::new();
+        let mut current_day = None;
+        for ((day, part), answer) in &self.expected {
+            if current_day != Some(*day) {
+                if current_day.is_some() {
+                    toml.push('\n');
+                }
+                toml.push_str(&format!("[day{}]\n", day));
+                current_day = Some(*day);
+            }
+            toml.push_str(&format!("part{} = {}\n", part, json::quote(answer)));
+        }
+        toml
+    }
+
+    pub fn expected(&self, day: u32, part: u8) -> Option<&str> {
+        self.expected.get(&(day, part)).map(|s| s.as_str())
+    }
+
+    pub fn record(&mut self, day: u32, part: u8, answer: &str) {
+        self.expected.insert((day, part), answer.to_string());
+    }
+}
+
+/// Reads back a TOML string. Answers are written with `json::quote`, whose escapes are all
+/// valid in TOML basic strings, so there is no TOML specific quoting.
+fn unquote(value: &str) -> Option<String> {
+    if let Some(literal) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        return Some(literal.to_string());
+    }
+    let inner = value.strip_prefix('"')?.strip_suffix('"')?;
+    let mut unquoted = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unquoted.push(match chars.next()? {
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                'u' => {
+                    let hex = chars.by_ref().take(4).collect::<String>();
+                    char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?
+                }
+                other => other,
+            }),
+            '"' => return None,
+            c => unquoted.push(c),
+        }
+    }
+    Some(unquoted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_sections() {
+        let answers = Answers::parse(
+            "# comment\n[day0]\npart1 = \"123EG\"\npart2 = '6'\n\n[day6]\npart1 = \"41\"\n",
+        )
+        .unwrap();
+        assert_eq!(answers.expected(0, 1), Some("123EG"));
+        assert_eq!(answers.expected(0, 2), Some("6"));
+        assert_eq!(answers.expected(6, 1), Some("41"));
+        assert_eq!(answers.expected(6, 2), None);
+    }
+
+    #[test]
+    fn parse_reports_line() {
+        let err = Answers::parse("[day1]\npart1 41").unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+    }
+
+    #[test]
+    fn round_trip_escapes() {
+        let mut answers = Answers::default();
+        answers.record(0, 1, "a\"b\\c\n#%\u{1}");
+        answers.record(3, 2, "48");
+        assert_eq!(Answers::parse(&answers.to_toml()).unwrap(), answers);
+    }
+}
@@ -0,0 +1,169 @@
+use crate::common::day::Day;
+use crate::common::error::AocError;
+use crate::common::registry::DayEntry;
+use std::time::{Duration, Instant};
+
+pub struct BenchOptions {
+    pub iterations: usize,
+    pub warmup: usize,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        BenchOptions {
+            iterations: 10,
+            warmup: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    fn from_samples(samples: &mut [Duration]) -> Self {
+        if samples.is_empty() {
+            return Stats {
+                min: Duration::ZERO,
+                median: Duration::ZERO,
+                mean: Duration::ZERO,
+                p95: Duration::ZERO,
+            };
+        }
+        samples.sort();
+        let total: Duration = samples.iter().sum();
+        let p95_index = (samples.len() * 95).div_ceil(100) - 1;
+        Stats {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            mean: total / samples.len() as u32,
+            p95: samples[p95_index],
+        }
+    }
+
+    fn to_json(self) -> String {
+        format!(
+            "{{\"min_ns\":{},\"median_ns\":{},\"mean_ns\":{},\"p95_ns\":{}}}",
+            self.min.as_nanos(),
+            self.median.as_nanos(),
+            self.mean.as_nanos(),
+            self.p95.as_nanos()
+        )
+    }
+}
+
+pub struct DayBench {
+    pub day: u32,
+    pub iterations: usize,
+    pub warmup: usize,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+
+impl DayBench {
+    pub fn print(&self) {
+        println!(
+            "Day {} ({} iterations, {} warm-up)",
+            self.day, self.iterations, self.warmup
+        );
+        for (name, stats) in [
+            ("parse", self.parse),
+            ("part 1", self.part1),
+            ("part 2", self.part2),
+        ] {
+            println!(
+                "  {:<7} min {:>10.2?}  median {:>10.2?}  mean {:>10.2?}  p95 {:>10.2?}",
+                name, stats.min, stats.median, stats.mean, stats.p95
+            );
+        }
+        // The parts parse their own input, so the solve time is what remains after parsing
+        println!(
+            "  solve   part 1 {:.2?}, part 2 {:.2?} (median, excluding parse)",
+            self.part1.median.saturating_sub(self.parse.median),
+            self.part2.median.saturating_sub(self.parse.median)
+        );
+    }
+
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"day\":{},\"iterations\":{},\"warmup\":{},\"parse\":{},\"part1\":{},\"part2\":{}}}",
+            self.day,
+            self.iterations,
+            self.warmup,
+            self.parse.to_json(),
+            self.part1.to_json(),
+            self.part2.to_json()
+        )
+    }
+}
+
+/// Runs parse and both parts `warmup` times untimed, then `iterations` times timed
+pub fn bench_day(
+    entry: &DayEntry,
+    input: String,
+    options: &BenchOptions,
+) -> Result<DayBench, AocError> {
+    let day = (entry.create)(input);
+    let parse = sample(&*day, options, |day| day.parse())?;
+    let part1 = sample(&*day, options, |day| day.part1().map(drop))?;
+    let part2 = sample(&*day, options, |day| day.part2().map(drop))?;
+    Ok(DayBench {
+        day: entry.number,
+        iterations: options.iterations,
+        warmup: options.warmup,
+        parse,
+        part1,
+        part2,
+    })
+}
+
+fn sample<F>(day: &dyn Day, options: &BenchOptions, run: F) -> Result<Stats, AocError>
+where
+    F: Fn(&dyn Day) -> Result<(), AocError>,
+{
+    for _ in 0..options.warmup {
+        run(day)?;
+    }
+    let mut samples = Vec::with_capacity(options.iterations);
+    for _ in 0..options.iterations {
+        let start = Instant::now();
+        run(day)?;
+        samples.push(start.elapsed());
+    }
+    Ok(Stats::from_samples(&mut samples))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::REGISTRY;
+
+    #[test]
+    fn stats_from_samples() {
+        let mut samples: Vec<Duration> = (1..=20).rev().map(Duration::from_millis).collect();
+        let stats = Stats::from_samples(&mut samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(11));
+        assert_eq!(stats.mean, Duration::from_micros(10_500));
+        assert_eq!(stats.p95, Duration::from_millis(19));
+    }
+
+    #[test]
+    fn bench_day_runs_requested_iterations() {
+        let options = BenchOptions {
+            iterations: 2,
+            warmup: 1,
+        };
+        let bench = bench_day(REGISTRY.find(0).unwrap(), "A1B2".to_string(), &options).unwrap();
+        assert_eq!(bench.iterations, 2);
+        assert!(bench
+            .to_json()
+            .starts_with("{\"day\":0,\"iterations\":2,\"warmup\":1,"));
+    }
+}
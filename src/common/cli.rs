@@ -0,0 +1,275 @@
+use crate::common::bench::BenchOptions;
+use crate::common::pool;
+use crate::common::render::RenderOptions;
+use crate::common::report::Format;
+use crate::common::runner::InputSource;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const USAGE: &str = "Usage: <program> <input_folder|-> <day|all> [--format text|json|junit] [--jobs N] [--record | --bench [--iterations N] [--warmup N]]
+       <program> <day> --file <path> | --input <text> [--bench ...]
+       <program> <input_folder|-> <day> --explain [--jobs N]
+       <program> <input_folder|-> <day> --render [--jobs N] [--animate [--delay MS] [--frames N]]
+       <program> new <day> [title]
+       <program> list";
+
+pub enum Target {
+    Day(String),
+    All,
+}
+
+pub enum Command {
+    List,
+    New {
+        number: u32,
+        title: Option<String>,
+    },
+    Run {
+        source: InputSource,
+        target: Target,
+        bench: Option<BenchOptions>,
+        record: bool,
+        format: Format,
+        /// Threads to solve days and parts on, also handed to the days for their own work.
+        /// Benchmarks always run serially.
+        jobs: usize,
+    },
+    Explain {
+        source: InputSource,
+        day: String,
+        jobs: usize,
+    },
+    Render {
+        source: InputSource,
+        day: String,
+        options: RenderOptions,
+        jobs: usize,
+    },
+}
+
+pub fn parse(args: &[String]) -> Result<Command, Box<dyn std::error::Error>> {
+    let mut positional = Vec::new();
+    let mut bench = false;
+    let mut record = false;
+    let mut bench_options = BenchOptions::default();
+    let mut source = None;
+    let mut format = Format::Text;
+    let mut jobs = 1;
+    let mut render = false;
+    let mut explain = false;
+    let mut render_options = RenderOptions::default();
+    let mut args = args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bench" => bench = true,
+            "--record" => record = true,
+            "--json" => format = Format::Json,
+            "--format" => {
+                format = args
+                    .next()
+                    .ok_or_else(|| format!("{} expects a value", arg))?
+                    .parse()?
+            }
+            "--explain" => explain = true,
+            "--render" => render = true,
+            "--animate" => render_options.animate = true,
+            "--delay" => {
+                render_options.delay = Duration::from_millis(parse_count(arg, args.next())? as u64)
+            }
+            "--frames" => render_options.frames = parse_count(arg, args.next())?,
+            "--iterations" => bench_options.iterations = parse_count(arg, args.next())?,
+            "--warmup" => bench_options.warmup = parse_count(arg, args.next())?,
+            "--jobs" => {
+                jobs = match parse_count(arg, args.next())? {
+                    0 => pool::available_jobs(),
+                    jobs => jobs,
+                }
+            }
+            "--file" | "--input" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("{} expects a value", arg))?;
+                if source.is_some() {
+                    return Err("Only one of --file and --input can be given".into());
+                }
+                source = Some(match arg.as_str() {
+                    "--file" => InputSource::File(PathBuf::from(value)),
+                    _ => InputSource::Inline(value.to_string()),
+                });
+            }
+            flag if flag.starts_with("--") => {
+                return Err(format!("Unknown option: {}", flag).into())
+            }
+            _ => positional.push(arg.as_str()),
+        }
+    }
+
+    let (source, day) = match (positional.as_slice(), source) {
+        (["list"], None) => return Ok(Command::List),
+        (["new", number, title @ ..], None) if title.len() <= 1 => {
+            return Ok(Command::New {
+                number: number
+                    .parse()
+                    .map_err(|_| format!("Expected a day number, got {}", number))?,
+                title: title.first().map(|t| t.to_string()),
+            })
+        }
+        (["-", day], None) => (InputSource::Stdin, *day),
+        ([input_folder, day], None) => (InputSource::Folder(PathBuf::from(input_folder)), *day),
+        ([day], Some(source)) => (source, *day),
+        _ => return Err(USAGE.into()),
+    };
+    if explain {
+        if day == "all" || bench || record || render {
+            return Err("--explain works on a single day and only on its own".into());
+        }
+        return Ok(Command::Explain {
+            source,
+            day: day.to_string(),
+            jobs,
+        });
+    }
+    if render {
+        if day == "all" || bench || record {
+            return Err(
+                "--render draws a single day and can't be combined with --bench or --record".into(),
+            );
+        }
+        return Ok(Command::Render {
+            source,
+            day: day.to_string(),
+            options: render_options,
+            jobs,
+        });
+    }
+    if bench && record {
+        return Err("--bench only times the solutions and can't be combined with --record".into());
+    }
+    let target = match day {
+        "all" if matches!(source, InputSource::Folder(_)) => Target::All,
+        "all" => return Err("Running all days needs an input folder".into()),
+        day => Target::Day(day.to_string()),
+    };
+    Ok(Command::Run {
+        source,
+        target,
+        bench: bench.then_some(bench_options),
+        record,
+        format,
+        jobs,
+    })
+}
+
+fn parse_count(flag: &str, value: Option<&String>) -> Result<usize, Box<dyn std::error::Error>> {
+    value
+        .and_then(|v| v.parse::<usize>().ok())
+        .ok_or_else(|| format!("{} expects a number", flag).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &str) -> Result<Command, Box<dyn std::error::Error>> {
+        let args = std::iter::once("aoc")
+            .chain(args.split(' '))
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
+        parse(&args)
+    }
+
+    fn source_of(args: &str) -> InputSource {
+        match parse_args(args).unwrap() {
+            Command::Run { source, .. } => source,
+            _ => panic!("expected a run command"),
+        }
+    }
+
+    #[test]
+    fn input_sources() {
+        assert_eq!(
+            source_of("input 3"),
+            InputSource::Folder(PathBuf::from("input"))
+        );
+        assert_eq!(source_of("- 3"), InputSource::Stdin);
+        assert_eq!(
+            source_of("3 --file example.txt"),
+            InputSource::File(PathBuf::from("example.txt"))
+        );
+        assert_eq!(
+            source_of("3 --input mul(2,4)"),
+            InputSource::Inline("mul(2,4)".to_string())
+        );
+    }
+
+    #[test]
+    fn output_format() {
+        let format_of = |args| match parse_args(args).unwrap() {
+            Command::Run { format, .. } => format,
+            _ => panic!("expected a run command"),
+        };
+        assert_eq!(format_of("input all"), Format::Text);
+        assert_eq!(format_of("input all --format junit"), Format::Junit);
+        assert_eq!(format_of("input 3 --json"), Format::Json);
+        assert!(parse_args("input all --format yaml").is_err());
+    }
+
+    #[test]
+    fn new_day() {
+        assert!(matches!(
+            parse_args("new 7 Bridge").unwrap(),
+            Command::New { number: 7, title: Some(title) } if title == "Bridge"
+        ));
+        assert!(parse_args("new seven").is_err());
+    }
+
+    #[test]
+    fn render_options() {
+        match parse_args("input 6 --render --animate --delay 20 --frames 5").unwrap() {
+            Command::Render { day, options, .. } => {
+                assert_eq!(day, "6");
+                assert!(options.animate);
+                assert_eq!(options.delay, Duration::from_millis(20));
+                assert_eq!(options.frames, 5);
+            }
+            _ => panic!("expected a render command"),
+        }
+        assert!(parse_args("input all --render").is_err());
+        assert!(parse_args("input 6 --render --bench").is_err());
+    }
+
+    #[test]
+    fn explain() {
+        assert!(matches!(
+            parse_args("6 --file example.txt --explain").unwrap(),
+            Command::Explain { day, jobs: 1, .. } if day == "6"
+        ));
+        assert!(matches!(
+            parse_args("input 6 --explain --jobs 4").unwrap(),
+            Command::Explain { jobs: 4, .. }
+        ));
+        assert!(parse_args("input all --explain").is_err());
+        assert!(parse_args("input 6 --explain --render").is_err());
+    }
+
+    #[test]
+    fn bench_options() {
+        match parse_args("input all --bench --iterations 3 --warmup 0").unwrap() {
+            Command::Run {
+                bench: Some(options),
+                ..
+            } => {
+                assert_eq!(options.iterations, 3);
+                assert_eq!(options.warmup, 0);
+            }
+            _ => panic!("expected a benchmark run"),
+        }
+        assert!(parse_args("input 6 --bench --record").is_err());
+    }
+
+    #[test]
+    fn rejects_all_without_folder() {
+        assert!(parse_args("- all").is_err());
+        assert!(parse_args("input 3 --input x").is_err());
+    }
+}
@@ -0,0 +1,183 @@
+//! Loop detection for simulations. `step` turns one state into the next and returns `None`
+//! once the simulation ends, in which case there is no cycle. Floyd and Brent need no memory
+//! but call `step` more than once per state, so it has to be a deterministic `Fn`.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<S> {
+    /// Number of steps before the first state that repeats
+    pub start: usize,
+    /// Number of states in the loop
+    pub length: usize,
+    /// The states before the loop, `prefix.len() == start`
+    pub prefix: Vec<S>,
+    /// The first state of the loop
+    pub entry: S,
+}
+
+/// Remembers every state in a map, the fastest when states are cheap to hash and store
+pub fn hashed<S, F>(initial: S, mut step: F) -> Option<Cycle<S>>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> Option<S>,
+{
+    let mut seen = HashMap::new();
+    let mut states = Vec::new();
+    let mut current = initial;
+    loop {
+        if let Some(&start) = seen.get(&current) {
+            states.truncate(start);
+            return Some(Cycle {
+                start,
+                length: seen.len() - start,
+                prefix: states,
+                entry: current,
+            });
+        }
+        seen.insert(current.clone(), states.len());
+        let next = step(&current)?;
+        states.push(current);
+        current = next;
+    }
+}
+
+/// Floyd's tortoise and hare
+pub fn floyd<S, F>(initial: S, step: F) -> Option<Cycle<S>>
+where
+    S: Clone + Eq,
+    F: Fn(&S) -> Option<S>,
+{
+    let mut tortoise = step(&initial)?;
+    let mut hare = step(&step(&initial)?)?;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&step(&hare)?)?;
+    }
+
+    let mut start = 0;
+    tortoise = initial.clone();
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise)?;
+    while tortoise != hare {
+        hare = step(&hare)?;
+        length += 1;
+    }
+
+    prefix_of(initial, start, length, step)
+}
+
+/// Brent's algorithm, fewer calls to `step` than Floyd for the same result
+pub fn brent<S, F>(initial: S, step: F) -> Option<Cycle<S>>
+where
+    S: Clone + Eq,
+    F: Fn(&S) -> Option<S>,
+{
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial)?;
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare)?;
+        length += 1;
+    }
+
+    tortoise = initial.clone();
+    hare = initial.clone();
+    for _ in 0..length {
+        hare = step(&hare)?;
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        start += 1;
+    }
+
+    prefix_of(initial, start, length, step)
+}
+
+fn prefix_of<S, F>(initial: S, start: usize, length: usize, step: F) -> Option<Cycle<S>>
+where
+    S: Clone,
+    F: Fn(&S) -> Option<S>,
+{
+    let mut prefix = Vec::with_capacity(start);
+    let mut current = initial;
+    for _ in 0..start {
+        let next = step(&current)?;
+        prefix.push(current);
+        current = next;
+    }
+    Some(Cycle {
+        start,
+        length,
+        prefix,
+        entry: current,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0 -> 1 -> 2 -> 3 -> ... -> 7 -> 3
+    fn rho(x: &u32) -> Option<u32> {
+        Some(if *x == 7 { 3 } else { x + 1 })
+    }
+
+    #[test]
+    fn detectors_agree() {
+        let expected = Some(Cycle {
+            start: 3,
+            length: 5,
+            prefix: vec![0, 1, 2],
+            entry: 3,
+        });
+        assert_eq!(hashed(0, rho), expected);
+        assert_eq!(floyd(0, rho), expected);
+        assert_eq!(brent(0, rho), expected);
+
+        let step = |x: &u64| Some((x * x + 1) % 2003);
+        for initial in 0..50 {
+            let cycle = hashed(initial, step);
+            assert_eq!(floyd(initial, step), cycle);
+            assert_eq!(brent(initial, step), cycle);
+        }
+    }
+
+    #[test]
+    fn pure_loop_and_fixed_point() {
+        let expected = Some(Cycle {
+            start: 0,
+            length: 3,
+            prefix: vec![],
+            entry: 0,
+        });
+        let step = |x: &u32| Some((x + 1) % 3);
+        assert_eq!(hashed(0, step), expected);
+        assert_eq!(floyd(0, step), expected);
+        assert_eq!(brent(0, step), expected);
+        assert_eq!(brent(4, |x: &u32| Some(*x)).map(|c| c.length), Some(1));
+    }
+
+    #[test]
+    fn ending_simulation_has_no_cycle() {
+        let step = |x: &u32| (*x < 10).then_some(x + 1);
+        assert_eq!(hashed(0, step), None);
+        assert_eq!(floyd(0, step), None);
+        assert_eq!(brent(0, step), None);
+    }
+}
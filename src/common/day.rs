@@ -1,4 +1,26 @@
-pub trait Day {
-    fn part1(&self) -> Result<String, Box<dyn std::error::Error>>;
-    fn part2(&self) -> Result<String, Box<dyn std::error::Error>>;
+use crate::common::answer::Answer;
+use crate::common::error::AocError;
+
+/// Days have to be `Send + Sync`, so the runner can solve both parts and several days in parallel
+pub trait Day: Send + Sync {
+    /// Parses the input without solving anything, so the benchmark can time it separately.
+    /// Days without a separate parse step can keep the default.
+    fn parse(&self) -> Result<(), AocError> {
+        Ok(())
+    }
+    fn part1(&self) -> Result<Answer, AocError>;
+    fn part2(&self) -> Result<Answer, AocError>;
+    /// The `--jobs` count, for days that spread the work of a single part over threads.
+    /// Called before any part runs; single threaded days keep the default and ignore it.
+    fn set_jobs(&mut self, _jobs: usize) {}
+    /// The details behind the answers for `--explain`, one per line so they can be diffed
+    /// against another solution. Days with nothing to explain keep the default and return none.
+    fn explain(&self) -> Result<Vec<String>, AocError> {
+        Ok(Vec::new())
+    }
+    /// Pictures of the puzzle for `--render`: up to `frames` steps of the solution followed by
+    /// the final state. Days that can't draw themselves keep the default and return none.
+    fn render(&self, _frames: usize) -> Result<Vec<String>, AocError> {
+        Ok(Vec::new())
+    }
 }
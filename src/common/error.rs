@@ -0,0 +1,122 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug)]
+pub enum AocError {
+    /// A file or a required part of the puzzle input is not there
+    MissingInput(String),
+    /// A line of the puzzle input could not be understood, line and column are 1-based
+    MalformedLine {
+        day: u32,
+        line: usize,
+        column: usize,
+        text: String,
+        reason: String,
+    },
+    UnsupportedDay {
+        day: String,
+        available: Vec<u32>,
+    },
+    Solver {
+        day: u32,
+        reason: String,
+    },
+    Io(std::io::Error),
+}
+
+impl AocError {
+    /// Builds a `MalformedLine` for `token`, which has to be a slice of `line`.
+    /// `line_index` is 0-based, as given by `enumerate`
+    pub fn malformed(
+        day: u32,
+        line_index: usize,
+        line: &str,
+        token: &str,
+        reason: impl Display,
+    ) -> Self {
+        AocError::MalformedLine {
+            day,
+            line: line_index + 1,
+            column: column_of(line, token),
+            text: token.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// The 1-based column where `token` starts, counted in characters. `token` has to be a slice
+/// of `line`.
+pub fn column_of(line: &str, token: &str) -> usize {
+    let offset = (token.as_ptr() as usize).saturating_sub(line.as_ptr() as usize);
+    line.get(..offset).map_or(0, |l| l.chars().count()) + 1
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AocError::MissingInput(what) => write!(f, "Missing input: {}", what),
+            AocError::MalformedLine {
+                day,
+                line,
+                column,
+                text,
+                reason,
+            } => write!(
+                f,
+                "Day {} input line {}, column {}: {} in '{}'",
+                day, line, column, reason, text
+            ),
+            AocError::UnsupportedDay { day, available } => write!(
+                f,
+                "No implementation known for day: {}. Available days: {}",
+                day,
+                available
+                    .iter()
+                    .map(|d| d.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            AocError::Solver { day, reason } => write!(f, "Day {} failed: {}", day, reason),
+            AocError::Io(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for AocError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AocError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for AocError {
+    fn from(e: std::io::Error) -> Self {
+        AocError::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_points_at_token() {
+        let line = "  7 6 x 2";
+        let err = AocError::malformed(2, 4, line, &line[6..7], "invalid digit");
+        assert!(matches!(
+            &err,
+            AocError::MalformedLine { day: 2, line: 5, column: 7, text, .. } if text == "x"
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Day 2 input line 5, column 7: invalid digit in 'x'"
+        );
+    }
+
+    #[test]
+    fn column_counts_characters() {
+        let line = "é→x";
+        assert_eq!(column_of(line, &line[5..]), 3);
+    }
+}
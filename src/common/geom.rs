@@ -0,0 +1,167 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A position or offset on a grid, `y` grows downwards like the lines of the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Coord {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Coord {
+    pub const fn new(x: i32, y: i32) -> Self {
+        Coord { x, y }
+    }
+
+    pub fn manhattan(self, other: Coord) -> u32 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The neighbouring coordinate in `direction`
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.delta()
+    }
+}
+
+impl Add for Coord {
+    type Output = Coord;
+
+    fn add(self, rhs: Coord) -> Coord {
+        Coord::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign for Coord {
+    fn add_assign(&mut self, rhs: Coord) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Coord {
+    type Output = Coord;
+
+    fn sub(self, rhs: Coord) -> Coord {
+        Coord::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign for Coord {
+    fn sub_assign(&mut self, rhs: Coord) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<i32> for Coord {
+    type Output = Coord;
+
+    fn mul(self, rhs: i32) -> Coord {
+        Coord::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Neg for Coord {
+    type Output = Coord;
+
+    fn neg(self) -> Coord {
+        Coord::new(-self.x, -self.y)
+    }
+}
+
+/// The eight compass directions, in clockwise order starting at north (up)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    /// Up, right, down and left
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    pub fn delta(self) -> Coord {
+        match self {
+            Direction::North => Coord::new(0, -1),
+            Direction::NorthEast => Coord::new(1, -1),
+            Direction::East => Coord::new(1, 0),
+            Direction::SouthEast => Coord::new(1, 1),
+            Direction::South => Coord::new(0, 1),
+            Direction::SouthWest => Coord::new(-1, 1),
+            Direction::West => Coord::new(-1, 0),
+            Direction::NorthWest => Coord::new(-1, -1),
+        }
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        (self as usize) % 2 == 1
+    }
+
+    /// Rotates clockwise in steps of 45 degrees, negative steps go counterclockwise
+    pub fn rotate(self, eighths: i32) -> Self {
+        Direction::ALL[(self as i32 + eighths).rem_euclid(8) as usize]
+    }
+
+    /// 90 degrees clockwise
+    pub fn turn_right(self) -> Self {
+        self.rotate(2)
+    }
+
+    /// 90 degrees counterclockwise
+    pub fn turn_left(self) -> Self {
+        self.rotate(-2)
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotate(4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coord_arithmetic() {
+        let a = Coord::new(2, 3);
+        let b = Coord::new(-1, 5);
+        assert_eq!(a + b, Coord::new(1, 8));
+        assert_eq!(a - b, Coord::new(3, -2));
+        assert_eq!(a * 3, Coord::new(6, 9));
+        assert_eq!(-a, Coord::new(-2, -3));
+        assert_eq!(a.manhattan(b), 5);
+        assert_eq!(a.step(Direction::North), Coord::new(2, 2));
+    }
+
+    #[test]
+    fn turning() {
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+        assert_eq!(Direction::NorthEast.turn_right(), Direction::SouthEast);
+        assert_eq!(Direction::SouthWest.opposite(), Direction::NorthEast);
+        assert_eq!(Direction::West.rotate(1), Direction::NorthWest);
+        for direction in Direction::ALL {
+            assert_eq!(direction.opposite().delta(), -direction.delta());
+            assert_eq!(direction.turn_right().turn_left(), direction);
+        }
+        assert!(Direction::CARDINAL.iter().all(|d| !d.is_diagonal()));
+    }
+}
@@ -0,0 +1,139 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::Hash;
+
+/// Nodes that depend on each other in a circle, each one has an edge to the next and the last
+/// one back to the first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<N>(pub Vec<N>);
+
+impl<N: Debug> Display for Cycle<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for node in &self.0 {
+            write!(f, "{:?} -> ", node)?;
+        }
+        match self.0.first() {
+            Some(first) => write!(f, "{:?}", first),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Orders `nodes` so that every edge `a -> b` between two of them has `a` before `b`, using
+/// Kahn's algorithm. Edges to nodes outside of `nodes` are ignored, so a rule set for the whole
+/// puzzle can be used to sort a subset. Nodes are expected to be distinct; whenever several
+/// nodes are free to go next, the one given first wins, so unrelated nodes keep their order.
+pub fn toposort<N>(nodes: &[N], edges: &HashMap<N, Vec<N>>) -> Result<Vec<N>, Cycle<N>>
+where
+    N: Copy + Eq + Hash,
+{
+    let index = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (*node, i))
+        .collect::<HashMap<_, _>>();
+    let successors = nodes
+        .iter()
+        .map(|node| {
+            edges.get(node).map_or(Vec::new(), |targets| {
+                targets
+                    .iter()
+                    .filter_map(|t| index.get(t).copied())
+                    .collect()
+            })
+        })
+        .collect::<Vec<Vec<usize>>>();
+
+    let mut in_degree = vec![0; nodes.len()];
+    for &target in successors.iter().flatten() {
+        in_degree[target] += 1;
+    }
+    let mut free = (0..nodes.len())
+        .filter(|&i| in_degree[i] == 0)
+        .map(Reverse)
+        .collect::<BinaryHeap<_>>();
+    let mut order = Vec::with_capacity(nodes.len());
+    while let Some(Reverse(i)) = free.pop() {
+        order.push(nodes[i]);
+        for &target in &successors[i] {
+            in_degree[target] -= 1;
+            if in_degree[target] == 0 {
+                free.push(Reverse(target));
+            }
+        }
+    }
+
+    if order.len() == nodes.len() {
+        Ok(order)
+    } else {
+        Err(find_cycle(nodes, &successors, &in_degree))
+    }
+}
+
+/// Every node Kahn's algorithm could not place still has a predecessor that wasn't placed
+/// either, so walking predecessors from any of them has to run into a cycle
+fn find_cycle<N: Copy>(nodes: &[N], successors: &[Vec<usize>], in_degree: &[usize]) -> Cycle<N> {
+    let mut predecessor = vec![None; nodes.len()];
+    for (source, targets) in successors.iter().enumerate() {
+        if in_degree[source] > 0 {
+            for &target in targets.iter().filter(|&&t| in_degree[t] > 0) {
+                predecessor[target] = Some(source);
+            }
+        }
+    }
+
+    let mut step_of = vec![None; nodes.len()];
+    let mut path = Vec::new();
+    let mut current = in_degree
+        .iter()
+        .position(|&d| d > 0)
+        .expect("an unplaced node");
+    while step_of[current].is_none() {
+        step_of[current] = Some(path.len());
+        path.push(current);
+        current = predecessor[current].expect("unplaced nodes have an unplaced predecessor");
+    }
+    let start = step_of[current].unwrap();
+    Cycle(path[start..].iter().rev().map(|&i| nodes[i]).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(pairs: &[(i32, i32)]) -> HashMap<i32, Vec<i32>> {
+        let mut edges = HashMap::new();
+        for &(from, to) in pairs {
+            edges.entry(from).or_insert(Vec::new()).push(to);
+        }
+        edges
+    }
+
+    #[test]
+    fn sorts_subset_and_keeps_unrelated_order() {
+        let edges = rules(&[(97, 75), (75, 47), (47, 61), (61, 53), (5, 47)]);
+        assert_eq!(
+            toposort(&[61, 47, 75, 97, 13], &edges),
+            Ok(vec![97, 75, 47, 61, 13])
+        );
+    }
+
+    #[test]
+    fn reports_cycle() {
+        let edges = rules(&[(1, 2), (2, 3), (3, 1), (4, 1)]);
+        let cycle = toposort(&[4, 3, 2, 1], &edges).unwrap_err();
+        let mut nodes = cycle.0.clone();
+        nodes.sort();
+        assert_eq!(nodes, vec![1, 2, 3]);
+        // Every node has an edge to the next one
+        for (i, node) in cycle.0.iter().enumerate() {
+            let next = cycle.0[(i + 1) % cycle.0.len()];
+            assert!(edges[node].contains(&next));
+        }
+        assert_eq!(
+            toposort(&[7], &rules(&[(7, 7)])).unwrap_err().to_string(),
+            "7 -> 7"
+        );
+    }
+}
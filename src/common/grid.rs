@@ -0,0 +1,333 @@
+use crate::common::error::AocError;
+use crate::common::geom::{Coord, Direction};
+use std::fmt::{Display, Formatter};
+
+/// Reasons a text could not be turned into a grid, lines and columns are 1-based
+#[derive(Debug, PartialEq)]
+pub enum GridError {
+    Empty,
+    RaggedRow {
+        line: usize,
+        text: String,
+        width: usize,
+        expected: usize,
+    },
+    InvalidCell {
+        line: usize,
+        column: usize,
+        cell: char,
+        reason: String,
+    },
+}
+
+impl GridError {
+    /// Converts to the `AocError` of `day`: an empty grid is missing input, a ragged row is a
+    /// `MalformedLine` pointing just past the shorter of the two widths
+    pub fn for_day(self, day: u32) -> AocError {
+        match self {
+            GridError::Empty => AocError::MissingInput("Received an empty grid".into()),
+            GridError::RaggedRow {
+                line,
+                text,
+                width,
+                expected,
+            } => AocError::MalformedLine {
+                day,
+                line,
+                column: width.min(expected) + 1,
+                text,
+                reason: format!("row has {} cells, expected {}", width, expected),
+            },
+            GridError::InvalidCell {
+                line,
+                column,
+                cell,
+                reason,
+            } => AocError::MalformedLine {
+                day,
+                line,
+                column,
+                text: cell.to_string(),
+                reason,
+            },
+        }
+    }
+}
+
+/// A rectangular grid stored row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl Grid<char> {
+    pub fn chars(input: &str) -> Result<Self, GridError> {
+        Grid::parse(input, |_, c| Ok::<_, String>(c))
+    }
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from text, one row per line, mapping every character with `map`.
+    /// Blank lines around the grid and carriage returns are ignored, all rows need the same
+    /// width.
+    pub fn parse<F, E>(input: &str, mut map: F) -> Result<Self, GridError>
+    where
+        F: FnMut(Coord, char) -> Result<T, E>,
+        E: Display,
+    {
+        let lines = input
+            .lines()
+            .map(|l| l.trim_end_matches('\r'))
+            .enumerate()
+            .skip_while(|(_, l)| l.trim().is_empty())
+            .collect::<Vec<_>>();
+        let last = lines
+            .iter()
+            .rposition(|(_, l)| !l.trim().is_empty())
+            .ok_or(GridError::Empty)?;
+        let lines = &lines[..=last];
+
+        let width = lines[0].1.chars().count();
+        let mut cells = Vec::with_capacity(width * lines.len());
+        for (y, (i, line)) in lines.iter().enumerate() {
+            let row_width = line.chars().count();
+            if row_width != width {
+                return Err(GridError::RaggedRow {
+                    line: i + 1,
+                    text: line.to_string(),
+                    width: row_width,
+                    expected: width,
+                });
+            }
+            for (x, c) in line.chars().enumerate() {
+                let cell =
+                    map(Coord::new(x as i32, y as i32), c).map_err(|e| GridError::InvalidCell {
+                        line: i + 1,
+                        column: x + 1,
+                        cell: c,
+                        reason: e.to_string(),
+                    })?;
+                cells.push(cell);
+            }
+        }
+        Ok(Grid {
+            cells,
+            width,
+            height: lines.len(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, coord: Coord) -> bool {
+        coord.x >= 0
+            && coord.y >= 0
+            && (coord.x as usize) < self.width
+            && (coord.y as usize) < self.height
+    }
+
+    fn index(&self, coord: Coord) -> Option<usize> {
+        self.contains(coord)
+            .then(|| coord.y as usize * self.width + coord.x as usize)
+    }
+
+    /// The coordinate of the cell at `index`, counting row by row
+    pub fn coord(&self, index: usize) -> Option<Coord> {
+        (index < self.cells.len())
+            .then(|| Coord::new((index % self.width) as i32, (index / self.width) as i32))
+    }
+
+    pub fn get(&self, coord: Coord) -> Option<&T> {
+        self.index(coord).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, coord: Coord) -> Option<&mut T> {
+        self.index(coord).map(|i| &mut self.cells[i])
+    }
+
+    /// Replaces the cell at `coord`, returns false if it lies outside of the grid
+    pub fn set(&mut self, coord: Coord, value: T) -> bool {
+        match self.get_mut(coord) {
+            Some(cell) => {
+                *cell = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn coords(&self) -> impl Iterator<Item = Coord> + '_ {
+        (0..self.cells.len()).filter_map(|i| self.coord(i))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        self.coords().zip(self.cells.iter())
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    pub fn row(&self, y: usize) -> impl Iterator<Item = (Coord, &T)> {
+        self.ray(Coord::new(0, y as i32), Direction::East.delta())
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = (Coord, &T)> {
+        self.ray(Coord::new(x as i32, 0), Direction::South.delta())
+    }
+
+    /// From `start` towards the bottom right
+    pub fn diagonal(&self, start: Coord) -> impl Iterator<Item = (Coord, &T)> {
+        self.ray(start, Direction::SouthEast.delta())
+    }
+
+    /// From `start` towards the bottom left
+    pub fn anti_diagonal(&self, start: Coord) -> impl Iterator<Item = (Coord, &T)> {
+        self.ray(start, Direction::SouthWest.delta())
+    }
+
+    /// Walks from `start` in steps of `delta` until leaving the grid, `start` included
+    pub fn ray(&self, start: Coord, delta: Coord) -> impl Iterator<Item = (Coord, &T)> {
+        std::iter::successors(Some(start), move |c| Some(*c + delta))
+            .map_while(|c| self.get(c).map(|cell| (c, cell)))
+    }
+
+    /// The up to 4 orthogonal neighbours inside the grid
+    pub fn neighbours4(&self, coord: Coord) -> impl Iterator<Item = (Coord, &T)> {
+        self.neighbours(coord, &Direction::CARDINAL)
+    }
+
+    /// The up to 8 orthogonal and diagonal neighbours inside the grid
+    pub fn neighbours8(&self, coord: Coord) -> impl Iterator<Item = (Coord, &T)> {
+        self.neighbours(coord, &Direction::ALL)
+    }
+
+    fn neighbours<'a>(
+        &'a self,
+        coord: Coord,
+        directions: &'a [Direction],
+    ) -> impl Iterator<Item = (Coord, &'a T)> {
+        directions.iter().filter_map(move |direction| {
+            let neighbour = coord.step(*direction);
+            self.get(neighbour).map(|cell| (neighbour, cell))
+        })
+    }
+
+    pub fn find(&self, value: &T) -> Option<Coord>
+    where
+        T: PartialEq,
+    {
+        self.find_all(value).next()
+    }
+
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Coord> + 'a
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .filter(move |(_, cell)| *cell == value)
+            .map(|(coord, _)| coord)
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\nabc\r\ndef\n\n";
+
+    #[test]
+    fn parse_and_display() {
+        let grid = Grid::chars(EXAMPLE).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(Coord::new(2, 1)), Some(&'f'));
+        assert_eq!(grid.get(Coord::new(3, 0)), None);
+        assert_eq!(grid.get(Coord::new(-1, 0)), None);
+        assert_eq!(grid.to_string(), "abc\ndef");
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(Grid::chars("\n \n"), Err(GridError::Empty));
+        assert!(matches!(
+            Grid::chars("abc\nde"),
+            Err(GridError::RaggedRow {
+                line: 2,
+                width: 2,
+                expected: 3,
+                ..
+            })
+        ));
+        let invalid = Grid::parse("ab\ncx", |_, c| match c {
+            'x' => Err("unknown cell"),
+            c => Ok(c),
+        });
+        assert!(matches!(
+            invalid,
+            Err(GridError::InvalidCell {
+                line: 2,
+                column: 2,
+                cell: 'x',
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn lines_through_the_grid() {
+        let grid = Grid::chars("abc\ndef\nghi").unwrap();
+        let collect =
+            |it: &mut dyn Iterator<Item = (Coord, &char)>| it.map(|(_, c)| *c).collect::<String>();
+        assert_eq!(collect(&mut grid.row(1)), "def");
+        assert_eq!(collect(&mut grid.column(2)), "cfi");
+        assert_eq!(collect(&mut grid.diagonal(Coord::new(0, 0))), "aei");
+        assert_eq!(collect(&mut grid.anti_diagonal(Coord::new(2, 0))), "ceg");
+        assert_eq!(
+            collect(&mut grid.ray(Coord::new(2, 2), Coord::new(-1, -1))),
+            "iea"
+        );
+    }
+
+    #[test]
+    fn neighbours_and_find() {
+        let grid = Grid::chars("abc\ndef\nghi").unwrap();
+        assert_eq!(grid.neighbours4(Coord::new(0, 0)).count(), 2);
+        assert_eq!(grid.neighbours8(Coord::new(1, 1)).count(), 8);
+        assert_eq!(grid.neighbours8(Coord::new(2, 2)).count(), 3);
+        assert_eq!(grid.find(&'h'), Some(Coord::new(1, 2)));
+        assert_eq!(grid.find(&'z'), None);
+    }
+}
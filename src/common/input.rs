@@ -0,0 +1,249 @@
+//! Helpers for the usual puzzle input shapes: lists of numbers, columns, pairs and blank-line
+//! separated sections. Every value remembers its line, so errors point at the offending text.
+
+use crate::common::error::{self, AocError};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// A non-blank line of the input without surrounding whitespace or carriage return
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a> {
+    /// 0-based line number in the whole input
+    pub index: usize,
+    pub text: &'a str,
+    raw: &'a str,
+}
+
+/// A value that could not be parsed, line and column are 1-based
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineError {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+impl LineError {
+    /// `token` has to be a slice of the line's text
+    fn new(line: &Line, token: &str, reason: impl Display) -> Self {
+        LineError {
+            line: line.index + 1,
+            column: error::column_of(line.raw, token),
+            text: token.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    /// The same error as a `MalformedLine` of `day`, the position is kept as is
+    pub fn for_day(self, day: u32) -> AocError {
+        AocError::MalformedLine {
+            day,
+            line: self.line,
+            column: self.column,
+            text: self.text,
+            reason: self.reason,
+        }
+    }
+}
+
+/// The non-blank lines of `input`
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input.lines().enumerate().filter_map(|(index, raw)| {
+        let text = raw.trim();
+        (!text.is_empty()).then_some(Line { index, text, raw })
+    })
+}
+
+/// Groups the lines into sections separated by blank lines. Lines that only contain whitespace
+/// count as blank, so indented and CRLF inputs split the same way.
+pub fn sections(input: &str) -> Vec<Vec<Line<'_>>> {
+    let mut sections = Vec::new();
+    let mut current: Vec<Line> = Vec::new();
+    let mut previous = None;
+    for line in lines(input) {
+        if previous.is_some_and(|p| line.index > p + 1) {
+            sections.push(std::mem::take(&mut current));
+        }
+        previous = Some(line.index);
+        current.push(line);
+    }
+    if !current.is_empty() {
+        sections.push(current);
+    }
+    sections
+}
+
+fn parse_value<T>(line: &Line, token: &str) -> Result<T, LineError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    token.parse().map_err(|e| LineError::new(line, token, e))
+}
+
+/// The numbers of every line, separated by whitespace and/or commas. A comma needs a value on
+/// both sides, so `75,,47` and `,` are errors.
+pub fn ints_per_line<'a, T>(
+    lines: impl IntoIterator<Item = Line<'a>>,
+) -> Result<Vec<Vec<T>>, LineError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    lines
+        .into_iter()
+        .map(|line| {
+            let mut values = Vec::new();
+            for field in line.text.split(',') {
+                if field.trim().is_empty() {
+                    // Point at the comma after the field, or before it for a trailing one
+                    let start = field.as_ptr() as usize - line.text.as_ptr() as usize;
+                    let end = start + field.len();
+                    let comma = if end < line.text.len() {
+                        end
+                    } else {
+                        start - 1
+                    };
+                    let comma = &line.text[comma..comma + 1];
+                    return Err(LineError::new(&line, comma, "empty field"));
+                }
+                for token in field.split_whitespace() {
+                    values.push(parse_value(&line, token)?);
+                }
+            }
+            Ok(values)
+        })
+        .collect()
+}
+
+/// Splits lines of exactly `N` whitespace separated values into `N` columns
+pub fn columns<'a, const N: usize, T>(
+    lines: impl IntoIterator<Item = Line<'a>>,
+) -> Result<[Vec<T>; N], LineError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let mut columns: [Vec<T>; N] = std::array::from_fn(|_| Vec::new());
+    for line in lines {
+        let tokens = line.text.split_whitespace().collect::<Vec<_>>();
+        if tokens.len() != N {
+            return Err(LineError::new(
+                &line,
+                line.text,
+                format!("expected {} values, found {}", N, tokens.len()),
+            ));
+        }
+        for (column, token) in columns.iter_mut().zip(tokens) {
+            column.push(parse_value(&line, token)?);
+        }
+    }
+    Ok(columns)
+}
+
+/// Lines of the form `a<separator>b`, like `47|53`
+pub fn pairs_separated_by<'a, T>(
+    lines: impl IntoIterator<Item = Line<'a>>,
+    separator: &str,
+) -> Result<Vec<(T, T)>, LineError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    lines
+        .into_iter()
+        .map(|line| {
+            let (left, right) = line.text.split_once(separator).ok_or_else(|| {
+                LineError::new(&line, line.text, format!("expected '{}'", separator))
+            })?;
+            Ok((
+                parse_value(&line, left.trim())?,
+                parse_value(&line, right.trim())?,
+            ))
+        })
+        .collect()
+}
+
+/// Every integer in free text, e.g. `Button A: X+94, Y-34` gives `[94, -34]`.
+/// A `-` directly in front of the digits makes the number negative.
+pub fn extract_all_ints<T>(input: &str) -> Result<Vec<T>, LineError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let mut values = Vec::new();
+    for line in lines(input) {
+        let text = line.text;
+        let mut rest = text;
+        while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+            let digits = rest[start..]
+                .find(|c: char| !c.is_ascii_digit())
+                .map_or(rest.len(), |end| start + end);
+            let negative = rest[..start].ends_with('-');
+            let token_start = text.len() - rest.len() + start - usize::from(negative);
+            let token_end = text.len() - rest.len() + digits;
+            values.push(parse_value(&line, &text[token_start..token_end])?);
+            rest = &rest[digits..];
+        }
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections_tolerate_indentation_and_crlf() {
+        let input = "  47|53\r\n  97|13\r\n   \r\n  75,47\r\n\r\n\r\n1\r\n";
+        let sections = sections(input);
+        assert_eq!(sections.len(), 3);
+        assert_eq!(
+            sections[0].iter().map(|l| l.text).collect::<Vec<_>>(),
+            vec!["47|53", "97|13"]
+        );
+        assert_eq!(sections[1][0].index, 3);
+        assert_eq!(sections[2][0].text, "1");
+    }
+
+    #[test]
+    fn ints_and_columns() {
+        assert_eq!(
+            ints_per_line::<i32>(lines("7 6 4\n\n1,-2, 3")).unwrap(),
+            vec![vec![7, 6, 4], vec![1, -2, 3]]
+        );
+        let err = ints_per_line::<i32>(lines("75,,47")).unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.reason.as_str()),
+            (1, 4, "empty field")
+        );
+        let err = ints_per_line::<i32>(lines("1\n,")).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        let err = ints_per_line::<i32>(lines("1,2,")).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 4, ","));
+        let [left, right] = columns::<2, i32>(lines("3   4\n4   3")).unwrap();
+        assert_eq!((left, right), (vec![3, 4], vec![4, 3]));
+        let err = columns::<2, i32>(lines("3   4\n4")).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+    }
+
+    #[test]
+    fn pairs() {
+        assert_eq!(
+            pairs_separated_by::<i32>(lines("47|53\n97 | 13"), "|").unwrap(),
+            vec![(47, 53), (97, 13)]
+        );
+        let err = pairs_separated_by::<i32>(lines("47|53\n  97|x"), "|").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 6, "x"));
+    }
+
+    #[test]
+    fn extract_ints_from_text() {
+        assert_eq!(
+            extract_all_ints::<i64>("Button A: X+94, Y-34\nPrize: X=8400").unwrap(),
+            vec![94, -34, 8400]
+        );
+        let err = extract_all_ints::<u8>("a 1\nb 300").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 3, "300"));
+    }
+}
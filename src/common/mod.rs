@@ -1 +1,20 @@
+pub mod answer;
+pub mod answers;
+pub mod bench;
+pub mod cli;
+pub mod cycle;
 pub mod day;
+pub mod error;
+pub mod geom;
+pub mod graph;
+pub mod grid;
+pub mod input;
+pub mod json;
+pub mod parse;
+pub mod pool;
+pub mod registry;
+pub mod render;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod search;
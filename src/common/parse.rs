@@ -0,0 +1,398 @@
+//! A small parser-combinator toolkit for instruction-style puzzles.
+//!
+//! Parsers work on an [`Input`] cursor and either produce a value and the remaining input, or
+//! `None` when they don't match. [`scan`] runs a parser over a whole text and skips everything
+//! it doesn't understand, which is what most "corrupted memory" puzzles need.
+
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+/// Byte range of a match in the original text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.start..self.end]
+    }
+
+    /// 1-based line and column of the start of the span
+    pub fn line_column(&self, source: &str) -> (usize, usize) {
+        let before = &source[..self.start];
+        let line = before.matches('\n').count() + 1;
+        let column = before
+            .rfind('\n')
+            .map_or(before, |newline| &before[newline + 1..])
+            .chars()
+            .count()
+            + 1;
+        (line, column)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spanned<T> {
+    pub value: T,
+    pub span: Span,
+}
+
+/// A position in the text that is being parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Input<'a> {
+    source: &'a str,
+    pos: usize,
+}
+
+impl<'a> Input<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Input { source, pos: 0 }
+    }
+
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// The text that has not been parsed yet
+    pub fn rest(&self) -> &'a str {
+        &self.source[self.pos..]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.source.len()
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    /// Skips `bytes` bytes, which have to end on a character boundary
+    pub fn advance(&self, bytes: usize) -> Self {
+        Input {
+            source: self.source,
+            pos: (self.pos + bytes).min(self.source.len()),
+        }
+    }
+
+    /// Skips a single character
+    pub fn next_char(&self) -> Self {
+        self.advance(self.peek().map_or(0, |c| c.len_utf8()))
+    }
+
+    fn span_to(&self, end: Input<'a>) -> Span {
+        Span {
+            start: self.pos,
+            end: end.pos,
+        }
+    }
+}
+
+pub type ParseResult<'a, T> = Option<(T, Input<'a>)>;
+
+pub trait Parser<'a, T> {
+    fn parse(&self, input: Input<'a>) -> ParseResult<'a, T>;
+
+    /// Borrows the parser, so it can be combined or run more than once
+    fn by_ref(&self) -> impl Parser<'a, T> + '_ {
+        move |input: Input<'a>| self.parse(input)
+    }
+
+    fn map<U, F>(self, f: F) -> impl Parser<'a, U>
+    where
+        Self: Sized,
+        F: Fn(T) -> U,
+    {
+        move |input: Input<'a>| self.parse(input).map(|(value, rest)| (f(value), rest))
+    }
+
+    /// Both parsers in sequence, keeping both values
+    fn then<U, P>(self, next: P) -> impl Parser<'a, (T, U)>
+    where
+        Self: Sized,
+        P: Parser<'a, U>,
+    {
+        move |input: Input<'a>| {
+            let (first, rest) = self.parse(input)?;
+            let (second, rest) = next.parse(rest)?;
+            Some(((first, second), rest))
+        }
+    }
+
+    /// Both parsers in sequence, keeping the value of this one
+    fn left<U, P>(self, next: P) -> impl Parser<'a, T>
+    where
+        Self: Sized,
+        P: Parser<'a, U>,
+    {
+        self.then(next).map(|(first, _)| first)
+    }
+
+    /// Both parsers in sequence, keeping the value of `next`
+    fn right<U, P>(self, next: P) -> impl Parser<'a, U>
+    where
+        Self: Sized,
+        P: Parser<'a, U>,
+    {
+        self.then(next).map(|(_, second)| second)
+    }
+
+    /// Tries `other` when this parser does not match
+    fn or<P>(self, other: P) -> impl Parser<'a, T>
+    where
+        Self: Sized,
+        P: Parser<'a, T>,
+    {
+        move |input: Input<'a>| self.parse(input).or_else(|| other.parse(input))
+    }
+
+    fn optional(self) -> impl Parser<'a, Option<T>>
+    where
+        Self: Sized,
+    {
+        move |input: Input<'a>| match self.parse(input) {
+            Some((value, rest)) => Some((Some(value), rest)),
+            None => Some((None, input)),
+        }
+    }
+
+    fn spanned(self) -> impl Parser<'a, Spanned<T>>
+    where
+        Self: Sized,
+    {
+        move |input: Input<'a>| {
+            let (value, rest) = self.parse(input)?;
+            Some((
+                Spanned {
+                    value,
+                    span: input.span_to(rest),
+                },
+                rest,
+            ))
+        }
+    }
+}
+
+impl<'a, T, F> Parser<'a, T> for F
+where
+    F: Fn(Input<'a>) -> ParseResult<'a, T>,
+{
+    fn parse(&self, input: Input<'a>) -> ParseResult<'a, T> {
+        self(input)
+    }
+}
+
+/// Parses `source` from the start, the parser doesn't need to consume all of it
+pub fn parse_str<'a, T>(parser: impl Parser<'a, T>, source: &'a str) -> Option<T> {
+    parser.parse(Input::new(source)).map(|(value, _)| value)
+}
+
+pub fn literal<'a>(expected: &'static str) -> impl Parser<'a, &'a str> {
+    move |input: Input<'a>| {
+        input.rest().starts_with(expected).then(|| {
+            (
+                &input.rest()[..expected.len()],
+                input.advance(expected.len()),
+            )
+        })
+    }
+}
+
+/// The longest run of at least `min` characters matching `predicate`
+pub fn take_while<'a, F>(min: usize, predicate: F) -> impl Parser<'a, &'a str>
+where
+    F: Fn(char) -> bool,
+{
+    move |input: Input<'a>| {
+        let rest = input.rest();
+        let (count, len) = rest
+            .chars()
+            .take_while(|c| predicate(*c))
+            .fold((0, 0), |(count, len), c| (count + 1, len + c.len_utf8()));
+        (count >= min).then(|| (&rest[..len], input.advance(len)))
+    }
+}
+
+pub fn char_where<'a, F>(predicate: F) -> impl Parser<'a, char>
+where
+    F: Fn(char) -> bool,
+{
+    move |input: Input<'a>| {
+        let c = input.peek().filter(|c| predicate(*c))?;
+        Some((c, input.next_char()))
+    }
+}
+
+pub fn digits<'a>() -> impl Parser<'a, &'a str> {
+    take_while(1, |c| c.is_ascii_digit())
+}
+
+/// Zero or more whitespace characters
+pub fn whitespace<'a>() -> impl Parser<'a, &'a str> {
+    take_while(0, char::is_whitespace)
+}
+
+/// A run of digits, fails when it does not fit into `T`
+pub fn unsigned<'a, T: FromStr>() -> impl Parser<'a, T> {
+    move |input: Input<'a>| {
+        let (digits, rest) = digits().parse(input)?;
+        Some((digits.parse().ok()?, rest))
+    }
+}
+
+/// A run of digits with an optional leading `-` or `+`
+pub fn signed<'a, T: FromStr>() -> impl Parser<'a, T> {
+    move |input: Input<'a>| {
+        let (_, after_sign) = char_where(|c| c == '-' || c == '+')
+            .optional()
+            .parse(input)?;
+        let (_, rest) = digits().parse(after_sign)?;
+        let text = &input.rest()[..rest.position() - input.position()];
+        Some((text.parse().ok()?, rest))
+    }
+}
+
+/// A letter or underscore, followed by letters, digits and underscores
+pub fn identifier<'a>() -> impl Parser<'a, &'a str> {
+    move |input: Input<'a>| {
+        let (_, after_first) = char_where(|c| c.is_alphabetic() || c == '_').parse(input)?;
+        let (_, rest) = take_while(0, |c| c.is_alphanumeric() || c == '_').parse(after_first)?;
+        Some((&input.rest()[..rest.position() - input.position()], rest))
+    }
+}
+
+/// Zero or more repetitions
+pub fn many<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |mut input: Input<'a>| {
+        let mut values = Vec::new();
+        while let Some((value, rest)) = parser.parse(input) {
+            values.push(value);
+            if rest == input {
+                break;
+            }
+            input = rest;
+        }
+        Some((values, input))
+    }
+}
+
+/// One or more repetitions
+pub fn many1<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    let many = many(parser);
+    move |input: Input<'a>| many.parse(input).filter(|(values, _)| !values.is_empty())
+}
+
+/// One or more `parser` matches, separated by `separator`
+pub fn separated<'a, T, S>(
+    parser: impl Parser<'a, T>,
+    separator: impl Parser<'a, S>,
+) -> impl Parser<'a, Vec<T>> {
+    move |input: Input<'a>| {
+        let (first, mut input) = parser.parse(input)?;
+        let mut values = vec![first];
+        while let Some((value, rest)) = separator
+            .parse(input)
+            .and_then(|(_, rest)| parser.parse(rest))
+        {
+            values.push(value);
+            input = rest;
+        }
+        Some((values, input))
+    }
+}
+
+/// Finds every match of `parser` in `source`, skipping characters it does not match as noise
+pub fn scan<'a, T, P: Parser<'a, T>>(parser: P, source: &'a str) -> Scan<'a, T, P> {
+    Scan {
+        parser,
+        input: Input::new(source),
+        value: PhantomData,
+    }
+}
+
+pub struct Scan<'a, T, P> {
+    parser: P,
+    input: Input<'a>,
+    value: PhantomData<fn() -> T>,
+}
+
+impl<'a, T, P: Parser<'a, T>> Iterator for Scan<'a, T, P> {
+    type Item = Spanned<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.input.is_empty() {
+            let start = self.input;
+            match self.parser.parse(start) {
+                // An empty match would never get anywhere, treat it as noise
+                Some((value, rest)) if rest != start => {
+                    self.input = rest;
+                    return Some(Spanned {
+                        value,
+                        span: start.span_to(rest),
+                    });
+                }
+                _ => self.input = start.next_char(),
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literals_and_numbers() {
+        assert_eq!(parse_str(literal("mul"), "mul(1,2)"), Some("mul"));
+        assert_eq!(parse_str(literal("mul"), "mu"), None);
+        assert_eq!(parse_str(unsigned::<u32>(), "123abc"), Some(123));
+        assert_eq!(parse_str(unsigned::<u8>(), "300"), None);
+        assert_eq!(parse_str(signed::<i32>(), "-42,"), Some(-42));
+        assert_eq!(parse_str(signed::<i32>(), "-x"), None);
+        assert_eq!(parse_str(identifier(), "don_t2()"), Some("don_t2"));
+        assert_eq!(parse_str(identifier(), "2x"), None);
+    }
+
+    #[test]
+    fn sequences_and_alternatives() {
+        let pair = literal("(")
+            .right(unsigned::<i32>())
+            .left(literal(","))
+            .then(unsigned::<i32>())
+            .left(literal(")"));
+        assert_eq!(parse_str(pair.by_ref(), "(3,4)"), Some((3, 4)));
+        assert_eq!(parse_str(pair.by_ref(), "(3,4]"), None);
+
+        let on_off = literal("on")
+            .map(|_| true)
+            .or(literal("off").map(|_| false));
+        assert_eq!(parse_str(on_off.by_ref(), "off"), Some(false));
+        assert_eq!(parse_str(on_off.optional(), "maybe"), Some(None));
+    }
+
+    #[test]
+    fn repetition() {
+        let list = separated(unsigned::<i32>(), literal(",").left(whitespace()));
+        assert_eq!(parse_str(list, "1, 2,3,x"), Some(vec![1, 2, 3]));
+        assert_eq!(
+            parse_str(many(literal("ab")), "ababa"),
+            Some(vec!["ab", "ab"])
+        );
+        assert_eq!(parse_str(many1(literal("ab")), "ba"), None);
+    }
+
+    #[test]
+    fn scan_skips_noise_and_tracks_spans() {
+        let source = "x1\n?22 ab333";
+        let numbers = scan(unsigned::<u32>(), source).collect::<Vec<_>>();
+        assert_eq!(
+            numbers.iter().map(|n| n.value).collect::<Vec<_>>(),
+            vec![1, 22, 333]
+        );
+        assert_eq!(numbers[1].span, Span { start: 4, end: 6 });
+        assert_eq!(numbers[1].span.text(source), "22");
+        assert_eq!(numbers[2].span.line_column(source), (2, 7));
+    }
+}
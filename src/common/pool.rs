@@ -0,0 +1,72 @@
+use std::sync::Mutex;
+use std::thread;
+
+pub type Task<'a, T> = Box<dyn FnOnce() -> T + Send + 'a>;
+
+/// Runs `tasks` on up to `jobs` std threads. The results come back in the order of the tasks,
+/// no matter which thread finished first.
+pub fn run<'a, T: Send>(jobs: usize, tasks: Vec<Task<'a, T>>) -> Vec<T> {
+    let jobs = jobs.min(tasks.len());
+    if jobs <= 1 {
+        return tasks.into_iter().map(|task| task()).collect();
+    }
+
+    let mut slots: Vec<Option<T>> = Vec::with_capacity(tasks.len());
+    slots.resize_with(tasks.len(), || None);
+    let results = Mutex::new(slots);
+    let queue = Mutex::new(tasks.into_iter().enumerate());
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                // Take the lock only to fetch the next task, not while running it
+                let next = queue.lock().unwrap().next();
+                let Some((index, task)) = next else {
+                    break;
+                };
+                let result = task();
+                results.lock().unwrap()[index] = Some(result);
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.expect("Every task has run once the scope ends"))
+        .collect()
+}
+
+/// The number of threads to use for `--jobs 0`
+pub fn available_jobs() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn keeps_task_order() {
+        let tasks: Vec<Task<usize>> = (0..16usize)
+            .map(|i| {
+                Box::new(move || {
+                    // Let the early tasks finish last
+                    thread::sleep(Duration::from_millis(16 - i as u64));
+                    i
+                }) as Task<usize>
+            })
+            .collect();
+        assert_eq!(run(4, tasks), (0..16).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn runs_inline_for_one_job() {
+        let values = [1, 2, 3];
+        let tasks: Vec<Task<i32>> = values
+            .iter()
+            .map(|v| Box::new(move || v * 2) as Task<i32>)
+            .collect();
+        assert_eq!(run(1, tasks), vec![2, 4, 6]);
+    }
+}
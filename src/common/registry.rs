@@ -0,0 +1,61 @@
+use crate::common::day::Day;
+use crate::common::error::AocError;
+
+/// A single implemented day, registered by its module so the runner can build it without
+/// knowing the concrete type.
+pub struct DayEntry {
+    pub number: u32,
+    pub title: &'static str,
+    pub create: fn(String) -> Box<dyn Day>,
+}
+
+pub struct Registry {
+    entries: &'static [DayEntry],
+}
+
+impl Registry {
+    pub const fn new(entries: &'static [DayEntry]) -> Self {
+        Registry { entries }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &DayEntry> {
+        self.entries.iter()
+    }
+
+    pub fn find(&self, number: u32) -> Option<&DayEntry> {
+        self.entries.iter().find(|entry| entry.number == number)
+    }
+
+    /// Looks up a day from a command line argument, listing the known days if it does not exist
+    pub fn lookup(&self, day_arg: &str) -> Result<&DayEntry, AocError> {
+        day_arg
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .and_then(|number| self.find(number))
+            .ok_or_else(|| AocError::UnsupportedDay {
+                day: day_arg.to_string(),
+                available: self.entries.iter().map(|entry| entry.number).collect(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::error::AocError;
+    use crate::days::REGISTRY;
+
+    #[test]
+    fn lookup_known_day() {
+        let entry = REGISTRY.lookup("6").unwrap();
+        assert_eq!(entry.number, 6);
+        assert_eq!(entry.title, "Guard Gallivant");
+    }
+
+    #[test]
+    fn lookup_unknown_day_lists_available() {
+        let err = REGISTRY.lookup("42").err().unwrap();
+        assert!(matches!(&err, AocError::UnsupportedDay { day, .. } if day == "42"));
+        assert!(err.to_string().contains("Available days: 0, 1, 2"));
+    }
+}
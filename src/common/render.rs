@@ -0,0 +1,85 @@
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+/// Clears the terminal and moves the cursor to the top left
+const CLEAR: &str = "\x1b[2J\x1b[H";
+
+pub struct RenderOptions {
+    /// Play the steps as an animation instead of only printing the final picture
+    pub animate: bool,
+    pub delay: Duration,
+    /// Most animation steps to show before jumping to the final picture
+    pub frames: usize,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            animate: false,
+            delay: Duration::from_millis(100),
+            frames: 200,
+        }
+    }
+}
+
+impl RenderOptions {
+    /// How many steps a day should draw, the final picture comes on top
+    pub fn frame_limit(&self) -> usize {
+        if self.animate {
+            self.frames
+        } else {
+            0
+        }
+    }
+}
+
+/// Prints the pictures a day rendered. Animations redraw the screen for every frame and wait
+/// `delay` in between, otherwise only the last picture is printed.
+pub fn play<W: Write>(out: &mut W, pictures: &[String], options: &RenderOptions) -> io::Result<()> {
+    if !options.animate {
+        if let Some(last) = pictures.last() {
+            writeln!(out, "{}", last)?;
+        }
+        return Ok(());
+    }
+    for (i, picture) in pictures.iter().enumerate() {
+        if i > 0 {
+            thread::sleep(options.delay);
+        }
+        write!(out, "{}{}", CLEAR, picture)?;
+        writeln!(out, "\nframe {}/{}", i + 1, pictures.len())?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn still_picture_prints_only_the_last() {
+        let pictures = vec!["#.".to_string(), "#X".to_string()];
+        let mut out = Vec::new();
+        play(&mut out, &pictures, &RenderOptions::default()).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "#X\n");
+    }
+
+    #[test]
+    fn animation_clears_between_frames() {
+        let pictures = vec!["#.".to_string(), "#X".to_string()];
+        let options = RenderOptions {
+            animate: true,
+            delay: Duration::ZERO,
+            frames: 10,
+        };
+        let mut out = Vec::new();
+        play(&mut out, &pictures, &options).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.matches(CLEAR).count(), 2);
+        assert!(out.ends_with("#X\nframe 2/2\n"));
+        assert_eq!(options.frame_limit(), 10);
+        assert_eq!(RenderOptions::default().frame_limit(), 0);
+    }
+}
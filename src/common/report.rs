@@ -0,0 +1,152 @@
+use crate::common::json;
+use crate::common::runner::{PartResult, Status};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Junit,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "junit" => Ok(Format::Junit),
+            other => Err(format!(
+                "Unknown format: {}, expected json, junit or text",
+                other
+            )),
+        }
+    }
+}
+
+fn optional_json(value: Option<&str>) -> String {
+    value.map_or("null".to_string(), json::quote)
+}
+
+impl PartResult {
+    pub fn to_json(&self) -> String {
+        let error = match self.status {
+            Status::Error | Status::Skipped => self.message.as_deref(),
+            _ => None,
+        };
+        format!(
+            "{{\"day\":{},\"part\":{},\"answer\":{},\"expected\":{},\"status\":\"{}\",\"elapsed_ns\":{},\"error\":{}}}",
+            self.day,
+            self.part,
+            self.answer.as_ref().map_or("null".to_string(), |a| a.to_json()),
+            optional_json(self.expected.as_deref()),
+            self.status,
+            self.elapsed.as_nanos(),
+            optional_json(error)
+        )
+    }
+}
+
+pub fn to_json(results: &[PartResult]) -> String {
+    let records = results.iter().map(|r| r.to_json()).collect::<Vec<_>>();
+    format!("[{}]", records.join(","))
+}
+
+/// One test suite with a test case per day and part. Parts without a known answer are
+/// reported as skipped, so they don't count as passing checks.
+pub fn to_junit(results: &[PartResult]) -> String {
+    let count = |status: Status| results.iter().filter(|r| r.status == status).count();
+    let skipped = count(Status::Skipped) + count(Status::Unknown);
+    let time: f64 = results.iter().map(|r| r.elapsed.as_secs_f64()).sum();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuite name=\"advent_of_code_2024\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{:.6}\">\n",
+        results.len(),
+        count(Status::Fail),
+        count(Status::Error),
+        skipped,
+        time
+    ));
+    for result in results {
+        xml.push_str(&format!(
+            "  <testcase classname=\"day{}\" name=\"part{}\" time=\"{:.6}\">\n",
+            result.day,
+            result.part,
+            result.elapsed.as_secs_f64()
+        ));
+        let message = xml_escape(result.message.as_deref().unwrap_or_default());
+        match result.status {
+            Status::Pass => {}
+            Status::Fail => xml.push_str(&format!("    <failure message=\"{}\"/>\n", message)),
+            Status::Error => xml.push_str(&format!("    <error message=\"{}\"/>\n", message)),
+            Status::Skipped => xml.push_str(&format!("    <skipped message=\"{}\"/>\n", message)),
+            Status::Unknown => xml.push_str("    <skipped message=\"no expected answer\"/>\n"),
+        }
+        if let Some(answer) = &result.answer {
+            xml.push_str(&format!(
+                "    <system-out>{}</system-out>\n",
+                xml_escape(&answer.to_string())
+            ));
+        }
+        xml.push_str("  </testcase>\n");
+    }
+    xml.push_str("</testsuite>\n");
+    xml
+}
+
+fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::answer::Answer;
+    use std::time::Duration;
+
+    fn result(part: u8, status: Status, message: Option<&str>) -> PartResult {
+        PartResult {
+            day: 6,
+            part,
+            answer: Some(Answer::Integer(41)),
+            status,
+            elapsed: Duration::from_micros(1500),
+            message: message.map(|m| m.to_string()),
+            expected: None,
+        }
+    }
+
+    #[test]
+    fn json_record() {
+        let mut failed = result(1, Status::Fail, Some("expected 42"));
+        failed.expected = Some("42".to_string());
+        assert_eq!(
+            failed.to_json(),
+            "{\"day\":6,\"part\":1,\"answer\":{\"kind\":\"integer\",\"value\":41},\"expected\":\"42\",\"status\":\"FAIL\",\"elapsed_ns\":1500000,\"error\":null}"
+        );
+    }
+
+    #[test]
+    fn junit_counts_and_escapes() {
+        let results = [
+            result(1, Status::Pass, None),
+            result(2, Status::Error, Some("bad <input>")),
+        ];
+        let xml = to_junit(&results);
+        assert!(xml.contains("tests=\"2\" failures=\"0\" errors=\"1\" skipped=\"0\""));
+        assert!(xml.contains("<error message=\"bad &lt;input&gt;\"/>"));
+        assert!(xml.contains("<testcase classname=\"day6\" name=\"part1\" time=\"0.001500\">"));
+    }
+}
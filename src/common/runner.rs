@@ -0,0 +1,332 @@
+use crate::common::answer::Answer;
+use crate::common::answers::Answers;
+use crate::common::day::Day;
+use crate::common::error::AocError;
+use crate::common::pool::{self, Task};
+use crate::common::registry::{DayEntry, Registry};
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::Read;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const MAX_ANSWER_WIDTH: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail,
+    Unknown,
+    Error,
+    Skipped,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let status = match self {
+            Status::Pass => "PASS",
+            Status::Fail => "FAIL",
+            Status::Unknown => "UNKNOWN",
+            Status::Error => "ERROR",
+            Status::Skipped => "SKIPPED",
+        };
+        f.pad(status)
+    }
+}
+
+#[derive(Debug)]
+pub struct PartResult {
+    pub day: u32,
+    pub part: u8,
+    pub answer: Option<Answer>,
+    pub status: Status,
+    pub elapsed: Duration,
+    pub message: Option<String>,
+    /// The expected answer, if the answers file knows it
+    pub expected: Option<String>,
+}
+
+/// Where the puzzle input of a day is read from
+#[derive(Debug, PartialEq)]
+pub enum InputSource {
+    /// `<folder>/<day>.input`
+    Folder(PathBuf),
+    Stdin,
+    File(PathBuf),
+    Inline(String),
+}
+
+impl InputSource {
+    pub fn read(&self, day: u32) -> Result<String, AocError> {
+        match self {
+            InputSource::Folder(folder) => load_input(folder, day),
+            InputSource::Stdin => {
+                let mut buffer = String::new();
+                std::io::stdin().read_to_string(&mut buffer)?;
+                Ok(buffer)
+            }
+            InputSource::File(path) => {
+                if !path.is_file() {
+                    return Err(AocError::MissingInput(format!(
+                        "{} does not exist",
+                        path.display()
+                    )));
+                }
+                Ok(std::fs::read_to_string(path)?)
+            }
+            InputSource::Inline(input) => Ok(input.clone()),
+        }
+    }
+}
+
+/// Reads `<folder>/<day>.input`
+pub fn load_input(folder: &Path, day: u32) -> Result<String, AocError> {
+    if !folder.is_dir() {
+        return Err(AocError::MissingInput(format!(
+            "input folder {} does not exist",
+            folder.display()
+        )));
+    }
+    let input_file = folder.join(format!("{}.input", day));
+    if !input_file.is_file() {
+        return Err(AocError::MissingInput(format!(
+            "{} does not exist",
+            input_file.display()
+        )));
+    }
+    let mut buffer = String::new();
+    File::open(&input_file)?.read_to_string(&mut buffer)?;
+    Ok(buffer)
+}
+
+/// Creates the day for `input` and hands it the `--jobs` count
+pub fn create(entry: &DayEntry, input: String, jobs: usize) -> Box<dyn Day> {
+    let mut day = (entry.create)(input);
+    day.set_jobs(jobs);
+    day
+}
+
+/// Runs both parts of a day, in parallel when `jobs` is more than one
+pub fn run_day(entry: &DayEntry, input: String, jobs: usize) -> Vec<PartResult> {
+    run_days(vec![(entry, Ok(input))], jobs)
+}
+
+/// Runs every registered day, skipping the ones without an input file in `folder`
+pub fn run_all(registry: &Registry, folder: &Path, jobs: usize) -> Vec<PartResult> {
+    let inputs = registry
+        .iter()
+        .map(|entry| (entry, load_input(folder, entry.number)))
+        .collect();
+    run_days(inputs, jobs)
+}
+
+/// Every part of every day is a separate task, the results keep the order of `inputs`
+fn run_days(inputs: Vec<(&DayEntry, Result<String, AocError>)>, jobs: usize) -> Vec<PartResult> {
+    let days = inputs
+        .into_iter()
+        .map(|(entry, input)| (entry.number, input.map(|input| create(entry, input, jobs))))
+        .collect::<Vec<_>>();
+    let mut tasks: Vec<Task<PartResult>> = Vec::with_capacity(days.len() * 2);
+    for (number, day) in &days {
+        let number = *number;
+        match day {
+            Ok(day) => {
+                tasks.push(Box::new(move || run_part(number, 1, || day.part1())));
+                tasks.push(Box::new(move || run_part(number, 2, || day.part2())));
+            }
+            Err(e) => {
+                for part in 1..=2 {
+                    let message = e.to_string();
+                    tasks.push(Box::new(move || PartResult {
+                        day: number,
+                        part,
+                        answer: None,
+                        status: Status::Skipped,
+                        elapsed: Duration::ZERO,
+                        message: Some(message),
+                        expected: None,
+                    }));
+                }
+            }
+        }
+    }
+    pool::run(jobs, tasks)
+}
+
+fn run_part<F>(day: u32, part: u8, solve: F) -> PartResult
+where
+    F: FnOnce() -> Result<Answer, AocError>,
+{
+    let start = Instant::now();
+    // A panicking day should not take the other days down with it
+    let result = panic::catch_unwind(AssertUnwindSafe(solve)).unwrap_or_else(|payload| {
+        let reason = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panicked".to_string());
+        Err(AocError::Solver { day, reason })
+    });
+    let elapsed = start.elapsed();
+    match result {
+        Ok(answer) => PartResult {
+            day,
+            part,
+            answer: Some(answer),
+            status: Status::Unknown,
+            elapsed,
+            message: None,
+            expected: None,
+        },
+        Err(e) => PartResult {
+            day,
+            part,
+            answer: None,
+            status: Status::Error,
+            elapsed,
+            message: Some(e.to_string()),
+            expected: None,
+        },
+    }
+}
+
+/// Checks the answers of solved parts against the expected ones, failed parts get the
+/// expected answer as message
+pub fn verify(results: &mut [PartResult], answers: &Answers) {
+    for result in results.iter_mut() {
+        let (Some(answer), Some(expected)) =
+            (&result.answer, answers.expected(result.day, result.part))
+        else {
+            continue;
+        };
+        result.expected = Some(expected.to_string());
+        if answer.matches(expected) {
+            result.status = Status::Pass;
+        } else {
+            result.status = Status::Fail;
+            result.message = Some(format!("expected {}", expected));
+        }
+    }
+}
+
+/// Stores the answers of all solved parts as the new expected answers
+pub fn record(results: &[PartResult], answers: &mut Answers) {
+    for result in results {
+        if let Some(answer) = &result.answer {
+            answers.record(result.day, result.part, &answer.to_string());
+        }
+    }
+}
+
+pub fn print_table(results: &[PartResult]) {
+    println!(
+        "{:>3} | {:>4} | {:<width$} | {:<7} | {:>10}",
+        "Day",
+        "Part",
+        "Answer",
+        "Status",
+        "Time",
+        width = MAX_ANSWER_WIDTH
+    );
+    println!("{}", "-".repeat(MAX_ANSWER_WIDTH + 38));
+    for result in results {
+        let answer = match (&result.answer, &result.message) {
+            (Some(answer), Some(message)) => format!("{} ({})", answer, message),
+            (Some(answer), None) => answer.to_string(),
+            (None, Some(message)) => message.to_string(),
+            (None, None) => String::new(),
+        };
+        let elapsed = match result.status {
+            Status::Skipped => "-".to_string(),
+            _ => format!("{:.2?}", result.elapsed),
+        };
+        println!(
+            "{:>3} | {:>4} | {:<width$} | {:<7} | {:>10}",
+            result.day,
+            result.part,
+            truncate(&answer),
+            result.status,
+            elapsed,
+            width = MAX_ANSWER_WIDTH
+        );
+    }
+}
+
+fn truncate(answer: &str) -> String {
+    let first_line = answer.lines().next().unwrap_or("");
+    if first_line.chars().count() > MAX_ANSWER_WIDTH || first_line.len() < answer.trim_end().len() {
+        let mut truncated: String = first_line.chars().take(MAX_ANSWER_WIDTH - 3).collect();
+        truncated.push_str("...");
+        truncated
+    } else {
+        first_line.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::REGISTRY;
+
+    #[test]
+    fn run_day_reports_both_parts() {
+        let entry = REGISTRY.find(0).unwrap();
+        let results = run_day(entry, "A1B2C3DEFG".to_string(), 1);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].answer, Some(Answer::Text("123EG".to_string())));
+        assert_eq!(results[1].answer, Some(Answer::Integer(6)));
+        assert!(results.iter().all(|r| r.status == Status::Unknown));
+    }
+
+    #[test]
+    fn verify_marks_pass_and_fail() {
+        let entry = REGISTRY.find(0).unwrap();
+        let mut results = run_day(entry, "A1B2C3DEFG".to_string(), 1);
+        let answers = Answers::parse("[day0]\npart1 = \"123EG\"\npart2 = \"7\"").unwrap();
+        verify(&mut results, &answers);
+        assert_eq!(results[0].status, Status::Pass);
+        assert_eq!(results[1].status, Status::Fail);
+        assert_eq!(results[1].message.as_deref(), Some("expected 7"));
+    }
+
+    #[test]
+    fn run_part_reports_panics() {
+        let result = run_part(7, 1, || panic!("boom"));
+        assert_eq!(result.status, Status::Error);
+        assert_eq!(result.message.as_deref(), Some("Day 7 failed: boom"));
+    }
+
+    #[test]
+    fn parallel_run_keeps_order() {
+        let entry = REGISTRY.find(0).unwrap();
+        let results = run_day(entry, "A1B2C3DEFG".to_string(), 2);
+        assert_eq!(results[0].part, 1);
+        assert_eq!(results[0].answer, Some(Answer::Text("123EG".to_string())));
+        assert_eq!(results[1].part, 2);
+        assert_eq!(results[1].answer, Some(Answer::Integer(6)));
+    }
+
+    #[test]
+    fn run_all_skips_missing_inputs() {
+        let results = run_all(&REGISTRY, Path::new("does-not-exist"), 4);
+        assert_eq!(results.len(), REGISTRY.iter().count() * 2);
+        assert!(results.iter().all(|r| r.status == Status::Skipped));
+    }
+
+    #[test]
+    fn read_inline_and_file_sources() {
+        let inline = InputSource::Inline("A1B2".to_string());
+        assert_eq!(inline.read(0).unwrap(), "A1B2");
+        let missing = InputSource::File(PathBuf::from("does-not-exist.input"));
+        assert!(matches!(missing.read(0), Err(AocError::MissingInput(_))));
+    }
+
+    #[test]
+    fn truncate_long_answers() {
+        assert_eq!(truncate("123"), "123");
+        let long = "x".repeat(40);
+        assert_eq!(truncate(&long).chars().count(), MAX_ANSWER_WIDTH);
+        assert!(truncate("ab\ncd").ends_with("..."));
+    }
+}
@@ -0,0 +1,203 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const REGISTER_MACRO: &str = "register_days!(";
+
+/// Generates `src/days/dayN.rs`, registers it in `src/days/mod.rs` and creates an empty
+/// `input/N.input`. Existing files are never overwritten, an existing input is kept as is.
+/// Returns the files that were created or changed.
+pub fn new_day(
+    root: &Path,
+    number: u32,
+    title: Option<&str>,
+) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let module = format!("day{}", number);
+    let days_dir = root.join("src").join("days");
+    let day_file = days_dir.join(format!("{}.rs", module));
+    let mod_file = days_dir.join("mod.rs");
+    let input_dir = root.join("input");
+    let input_file = input_dir.join(format!("{}.input", number));
+
+    let registry = std::fs::read_to_string(&mod_file)?;
+    let registry = register(&registry, &module)?;
+    let title = title.map_or_else(|| format!("Day {}", number), |t| t.to_string());
+
+    // create_new refuses to replace a day that is already there
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&day_file)
+        .map_err(|e| format!("Could not create {}: {}", day_file.display(), e))?
+        .write_all(template(number, &title).as_bytes())?;
+    std::fs::write(&mod_file, registry)?;
+    let mut changed = vec![day_file, mod_file];
+
+    std::fs::create_dir_all(&input_dir)?;
+    if !input_file.exists() {
+        std::fs::write(&input_file, "")?;
+        changed.push(input_file);
+    }
+    Ok(changed)
+}
+
+/// Adds `module` to the `register_days!` list, keeping the days ordered by number
+fn register(registry: &str, module: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let start = registry
+        .find(REGISTER_MACRO)
+        .ok_or("Could not find register_days! in src/days/mod.rs")?;
+    let list_start = start + REGISTER_MACRO.len();
+    let list_end = list_start
+        + registry[list_start..]
+            .find(");")
+            .ok_or("Could not find the end of register_days!")?;
+
+    let mut modules = registry[list_start..list_end]
+        .split(',')
+        .map(|m| m.trim())
+        .filter(|m| !m.is_empty())
+        .collect::<Vec<_>>();
+    if modules.contains(&module) {
+        return Err(format!("{} is already registered", module).into());
+    }
+    modules.push(module);
+    modules.sort_by_key(|m| {
+        m.trim_start_matches("day")
+            .parse::<u32>()
+            .unwrap_or(u32::MAX)
+    });
+
+    // Same layout rustfmt would pick: one line if it fits, otherwise one day per line
+    let single_line = format!("{}{});", REGISTER_MACRO, modules.join(", "));
+    let list = if single_line.len() <= 100 {
+        single_line
+    } else {
+        let lines = modules
+            .iter()
+            .map(|m| format!("    {},\n", m))
+            .collect::<String>();
+        format!("{}\n{});", REGISTER_MACRO, lines)
+    };
+    Ok(format!(
+        "{}{}{}",
+        &registry[..start],
+        list,
+        &registry[list_end + 2..]
+    ))
+}
+
+fn template(number: u32, title: &str) -> String {
+    format!(
+        r#"use crate::common::answer::Answer;
+use crate::common::day::Day;
+use crate::common::error::AocError;
+use crate::common::registry::DayEntry;
+
+pub struct Day{number} {{
+    pub input: String,
+}}
+
+pub const REGISTRATION: DayEntry = DayEntry {{
+    number: {number},
+    title: "{title}",
+    create: |input| Box::new(Day{number} {{ input }}),
+}};
+
+impl Day for Day{number} {{
+    fn part1(&self) -> Result<Answer, AocError> {{
+        Err(AocError::Solver {{
+            day: {number},
+            reason: format!(
+                "Part 1 is not implemented yet, got {{}} lines of input",
+                self.input.lines().count()
+            ),
+        }})
+    }}
+
+    fn part2(&self) -> Result<Answer, AocError> {{
+        Err(AocError::Solver {{
+            day: {number},
+            reason: format!(
+                "Part 2 is not implemented yet, got {{}} lines of input",
+                self.input.lines().count()
+            ),
+        }})
+    }}
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+
+    #[test]
+    #[ignore = "example not filled in yet"]
+    fn part1_example() {{
+        let day = Day{number} {{
+            input: "".to_string(),
+        }};
+        assert_eq!(day.part1().unwrap(), Answer::Integer(0));
+    }}
+    #[test]
+    #[ignore = "example not filled in yet"]
+    fn part2_example() {{
+        let day = Day{number} {{
+            input: "".to_string(),
+        }};
+        assert_eq!(day.part2().unwrap(), Answer::Integer(0));
+    }}
+}}
+"#,
+        number = number,
+        title = title.replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn register_keeps_order() {
+        let registry = "use x;\n\nregister_days!(day0, day1, day10);\n";
+        assert_eq!(
+            register(registry, "day7").unwrap(),
+            "use x;\n\nregister_days!(day0, day1, day7, day10);\n"
+        );
+        assert!(register(registry, "day1").is_err());
+    }
+
+    #[test]
+    fn register_wraps_long_lists() {
+        let days = (0..20).map(|d| format!("day{}", d)).collect::<Vec<_>>();
+        let registry = format!("register_days!({});\n", days.join(", "));
+        let registered = register(&registry, "day20").unwrap();
+        assert!(registered.starts_with("register_days!(\n    day0,\n    day1,\n"));
+        assert!(registered.ends_with("    day20,\n);\n"));
+    }
+
+    #[test]
+    fn new_day_refuses_to_overwrite() {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        let days_dir = root.join("src").join("days");
+        std::fs::create_dir_all(&days_dir).unwrap();
+        std::fs::write(days_dir.join("mod.rs"), "register_days!(day0);\n").unwrap();
+
+        let created = new_day(&root, 7, Some("Bridge Repair")).unwrap();
+        assert_eq!(created.len(), 3);
+        let day = std::fs::read_to_string(days_dir.join("day7.rs")).unwrap();
+        assert!(day.contains("title: \"Bridge Repair\""));
+        assert!(root.join("input").join("7.input").is_file());
+        assert_eq!(
+            std::fs::read_to_string(days_dir.join("mod.rs")).unwrap(),
+            "register_days!(day0, day7);\n"
+        );
+
+        std::fs::write(days_dir.join("day7.rs"), "keep me").unwrap();
+        assert!(new_day(&root, 7, None).is_err());
+        assert_eq!(
+            std::fs::read_to_string(days_dir.join("day7.rs")).unwrap(),
+            "keep me"
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,228 @@
+//! Word searches on character grids: words along the eight compass directions and 2D stencils
+//! in any rotation or reflection.
+
+use crate::common::geom::{Coord, Direction};
+use crate::common::grid::{Grid, GridError};
+use std::collections::HashSet;
+
+/// A word spelled from `start` towards `direction`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WordMatch {
+    pub start: Coord,
+    pub direction: Direction,
+    /// Letters in the word
+    pub length: usize,
+}
+
+impl WordMatch {
+    pub fn cells(&self) -> impl Iterator<Item = Coord> {
+        let (start, delta) = (self.start, self.direction.delta());
+        (0..self.length as i32).map(move |i| start + delta * i)
+    }
+}
+
+/// A stencil found around `centre`, `cells` are the letters it covers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StencilMatch {
+    pub centre: Coord,
+    pub cells: Vec<Coord>,
+}
+
+/// Every place `word` can be read in the grid, in any of the eight directions. Palindromes are
+/// found once for every way they read, a single letter eight times.
+pub fn find_word(grid: &Grid<char>, word: &str) -> Vec<WordMatch> {
+    let length = word.chars().count();
+    let Some(first) = word.chars().next() else {
+        return Vec::new();
+    };
+    let mut matches = Vec::new();
+    for start in grid.find_all(&first) {
+        for direction in Direction::ALL {
+            let spelled = grid
+                .ray(start, direction.delta())
+                .take(length)
+                .map(|(_, c)| *c);
+            if spelled.eq(word.chars()) {
+                matches.push(WordMatch {
+                    start,
+                    direction,
+                    length,
+                });
+            }
+        }
+    }
+    matches
+}
+
+pub fn count_word(grid: &Grid<char>, word: &str) -> usize {
+    find_word(grid, word).len()
+}
+
+/// Letters at fixed offsets from a centre cell, like the X-MAS cross
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stencil {
+    /// Sorted, so equal shapes compare equal
+    cells: Vec<(Coord, char)>,
+}
+
+impl Stencil {
+    /// Reads a stencil from rows of text, `.` matches any letter. The centre is the middle cell,
+    /// rounded towards the top left for even sizes.
+    pub fn parse(pattern: &str) -> Result<Self, GridError> {
+        let grid = Grid::chars(pattern)?;
+        let centre = Coord::new(grid.width() as i32 / 2, grid.height() as i32 / 2);
+        Ok(Stencil::from_cells(
+            grid.iter()
+                .filter(|(_, c)| **c != '.')
+                .map(|(coord, c)| (coord - centre, *c))
+                .collect(),
+        ))
+    }
+
+    fn from_cells(mut cells: Vec<(Coord, char)>) -> Self {
+        cells.sort_by_key(|(coord, _)| (coord.y, coord.x));
+        Stencil { cells }
+    }
+
+    /// A quarter turn clockwise around the centre
+    pub fn rotated(&self) -> Self {
+        Stencil::from_cells(
+            self.cells
+                .iter()
+                .map(|(c, letter)| (Coord::new(-c.y, c.x), *letter))
+                .collect(),
+        )
+    }
+
+    /// Mirrored left to right
+    pub fn reflected(&self) -> Self {
+        Stencil::from_cells(
+            self.cells
+                .iter()
+                .map(|(c, letter)| (Coord::new(-c.x, c.y), *letter))
+                .collect(),
+        )
+    }
+
+    /// All four rotations, with and without reflection, leaving out the ones that look the same
+    pub fn orientations(&self) -> Vec<Stencil> {
+        let mut orientations: Vec<Stencil> = Vec::with_capacity(8);
+        for start in [self.clone(), self.reflected()] {
+            let mut stencil = start;
+            for _ in 0..4 {
+                let next = stencil.rotated();
+                if !orientations.contains(&stencil) {
+                    orientations.push(stencil);
+                }
+                stencil = next;
+            }
+        }
+        orientations
+    }
+
+    pub fn matches(&self, grid: &Grid<char>, centre: Coord) -> bool {
+        self.cells
+            .iter()
+            .all(|(offset, letter)| grid.get(centre + *offset) == Some(letter))
+    }
+}
+
+/// Every place `stencil` matches in any orientation, once per matching orientation
+pub fn find_stencil(grid: &Grid<char>, stencil: &Stencil) -> Vec<StencilMatch> {
+    let orientations = stencil.orientations();
+    grid.coords()
+        .flat_map(|centre| {
+            orientations
+                .iter()
+                .filter(move |o| o.matches(grid, centre))
+                .map(move |o| StencilMatch {
+                    centre,
+                    cells: o.cells.iter().map(|(offset, _)| centre + *offset).collect(),
+                })
+        })
+        .collect()
+}
+
+pub fn count_stencil(grid: &Grid<char>, stencil: &Stencil) -> usize {
+    find_stencil(grid, stencil).len()
+}
+
+/// The grid with every letter outside of `cells` replaced by `.`, like the puzzle texts show
+/// where words were found
+pub fn highlight(grid: &Grid<char>, cells: &HashSet<Coord>) -> String {
+    let mut picture = String::with_capacity((grid.width() + 1) * grid.height());
+    for (coord, letter) in grid.iter() {
+        if coord.x == 0 && coord.y > 0 {
+            picture.push('\n');
+        }
+        picture.push(if cells.contains(&coord) { *letter } else { '.' });
+    }
+    picture
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_in_all_directions() {
+        let grid = Grid::chars("..X...\n.SAMX.\n.A..A.\nXMAS.S\n.X....").unwrap();
+        let matches = find_word(&grid, "XMAS");
+        assert_eq!(
+            matches,
+            vec![
+                WordMatch {
+                    start: Coord::new(2, 0),
+                    direction: Direction::SouthEast,
+                    length: 4,
+                },
+                WordMatch {
+                    start: Coord::new(4, 1),
+                    direction: Direction::West,
+                    length: 4,
+                },
+                WordMatch {
+                    start: Coord::new(0, 3),
+                    direction: Direction::East,
+                    length: 4,
+                },
+                WordMatch {
+                    start: Coord::new(1, 4),
+                    direction: Direction::North,
+                    length: 4,
+                },
+            ]
+        );
+        assert_eq!(count_word(&grid, "SAM"), 4);
+        assert_eq!(
+            highlight(&grid, &matches[1].cells().collect()),
+            "......\n.SAMX.\n......\n......\n......"
+        );
+        assert_eq!(count_word(&grid, "MM"), 0);
+        assert_eq!(count_word(&grid, ""), 0);
+    }
+
+    #[test]
+    fn stencil_orientations() {
+        let cross = Stencil::parse("M.S\n.A.\nM.S").unwrap();
+        assert_eq!(cross.orientations().len(), 4);
+        assert_eq!(Stencil::parse("A").unwrap().orientations().len(), 1);
+        assert_eq!(Stencil::parse("AB").unwrap().orientations().len(), 4);
+        assert_eq!(Stencil::parse("AB\nC.").unwrap().orientations().len(), 8);
+        assert_eq!(cross.rotated().rotated().rotated().rotated(), cross);
+    }
+
+    #[test]
+    fn stencil_matches() {
+        let grid = Grid::chars("S.S.\n.A..\nM.M.\n....").unwrap();
+        let cross = Stencil::parse("M.S\n.A.\nM.S").unwrap();
+        let found = find_stencil(&grid, &cross);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].centre, Coord::new(1, 1));
+        assert_eq!(
+            highlight(&grid, &found[0].cells.iter().copied().collect()),
+            "S.S.\n.A..\nM.M.\n...."
+        );
+        assert_eq!(count_stencil(&grid, &Stencil::parse("S.S").unwrap()), 1);
+    }
+}
@@ -1,12 +1,21 @@
+use crate::common::answer::Answer;
 use crate::common::day::Day;
+use crate::common::error::AocError;
+use crate::common::registry::DayEntry;
 
 pub struct Day0 {
     pub input: String,
 }
 
+pub const REGISTRATION: DayEntry = DayEntry {
+    number: 0,
+    title: "Framework Test",
+    create: |input| Box::new(Day0 { input }),
+};
+
 /// This is just a testing day, to make sure the 'framework' works
 impl Day for Day0 {
-    fn part1(&self) -> Result<String, Box<dyn std::error::Error>> {
+    fn part1(&self) -> Result<Answer, AocError> {
         //Get the even chars from string and concat
         Ok(self
             .input
@@ -15,10 +24,11 @@ impl Day for Day0 {
             .enumerate()
             .filter(|(i, _)| (i + 1) % 2 == 0)
             .map(|(_, ch)| ch)
-            .collect::<String>())
+            .collect::<String>()
+            .into())
     }
 
-    fn part2(&self) -> Result<String, Box<dyn std::error::Error>> {
+    fn part2(&self) -> Result<Answer, AocError> {
         //Get the even chars from string and concat, if number add to the result and return string
         Ok(self
             .input
@@ -28,12 +38,13 @@ impl Day for Day0 {
             .filter(|(i, _)| (i + 1) % 2 == 0)
             .map(|(_, ch)| ch.to_digit(10).unwrap_or(0))
             .sum::<u32>()
-            .to_string())
+            .into())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::common::answer::Answer;
     use crate::common::day::Day;
     use crate::days::day0::Day0;
 
@@ -42,13 +53,13 @@ mod tests {
         let day = Day0 {
             input: "A1B2C3DEFG".to_string(),
         };
-        assert_eq!(day.part1().unwrap().trim(), "123EG");
+        assert_eq!(day.part1().unwrap(), Answer::Text("123EG".to_string()));
     }
     #[test]
     fn part2_example() {
         let day = Day0 {
             input: "A1B2C3DEFG".to_string(),
         };
-        assert_eq!(day.part2().unwrap().trim(), "6");
+        assert_eq!(day.part2().unwrap(), Answer::Integer(6));
     }
 }
@@ -1,16 +1,27 @@
+use crate::common::answer::Answer;
 use crate::common::day::Day;
+use crate::common::error::AocError;
+use crate::common::input;
+use crate::common::registry::DayEntry;
 use std::collections::HashMap;
-use std::num::ParseIntError;
 pub struct Day1 {
     pub input: String,
 }
 
+pub const REGISTRATION: DayEntry = DayEntry {
+    number: 1,
+    title: "Historian Hysteria",
+    create: |input| Box::new(Day1 { input }),
+};
+
 impl Day for Day1 {
-    fn part1(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let (left, right) = self.get_left_right();
+    fn parse(&self) -> Result<(), AocError> {
+        self.get_left_right()?;
+        Ok(())
+    }
 
-        let mut left: Vec<i32> = left.collect::<Result<Vec<_>, _>>()?;
-        let mut right: Vec<i32> = right.collect::<Result<Vec<_>, _>>()?;
+    fn part1(&self) -> Result<Answer, AocError> {
+        let (mut left, mut right) = self.get_left_right()?;
         left.sort();
         right.sort();
 
@@ -19,49 +30,36 @@ impl Day for Day1 {
             .zip(right.iter())
             .map(|(l, r)| l.abs_diff(*r))
             .sum::<u32>()
-            .to_string())
+            .into())
     }
 
-    fn part2(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let (left, right) = self.get_left_right();
+    fn part2(&self) -> Result<Answer, AocError> {
+        let (left, right) = self.get_left_right()?;
         let mut map: HashMap<i32, i32> = HashMap::new();
         for r in right {
-            map.entry(r?).and_modify(|v| *v += 1).or_insert(1);
+            map.entry(r).and_modify(|v| *v += 1).or_insert(1);
         }
-        let folded: Result<i32, ParseIntError> = left.into_iter().try_fold(0, |acc, l| {
-            let mapped_value = l.map(|x| map.get(&x).unwrap_or(&0) * x)?;
-            Ok(acc + mapped_value)
-        });
-
-        Ok(folded?.to_string())
+        Ok(left
+            .into_iter()
+            .map(|l| map.get(&l).unwrap_or(&0) * l)
+            .sum::<i32>()
+            .into())
     }
 }
 
 impl Day1 {
-    fn get_left_right(
-        &self,
-    ) -> (
-        impl Iterator<Item = Result<i32, ParseIntError>> + '_,
-        impl Iterator<Item = Result<i32, ParseIntError>> + '_,
-    ) {
-        let left = self
-            .input
-            .split_whitespace()
-            .step_by(2)
-            .map(|s| s.parse::<i32>());
-        let right = self
-            .input
-            .split_whitespace()
-            .skip(1)
-            .step_by(2)
-            .map(|s| s.parse::<i32>());
-        (left, right)
+    fn get_left_right(&self) -> Result<(Vec<i32>, Vec<i32>), AocError> {
+        let [left, right] =
+            input::columns::<2, i32>(input::lines(&self.input)).map_err(|e| e.for_day(1))?;
+        Ok((left, right))
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::common::answer::Answer;
     use crate::common::day::Day;
+    use crate::common::error::AocError;
     use crate::days::day1::Day1;
 
     #[test]
@@ -69,13 +67,28 @@ mod tests {
         let day = Day1 {
             input: "3   4\n4   3\n2   5\n1   3\n3   9\n3   3".to_string(),
         };
-        assert_eq!(day.part1().unwrap().trim(), "11");
+        assert_eq!(day.part1().unwrap(), Answer::Integer(11));
     }
     #[test]
     fn part2_example() {
         let day = Day1 {
             input: "3   4\n4   3\n2   5\n1   3\n3   9\n3   3".to_string(),
         };
-        assert_eq!(day.part2().unwrap().trim(), "31");
+        assert_eq!(day.part2().unwrap(), Answer::Integer(31));
+    }
+    #[test]
+    fn malformed_line() {
+        let day = Day1 {
+            input: "3   4\n4   x".to_string(),
+        };
+        assert!(matches!(
+            day.part1(),
+            Err(AocError::MalformedLine {
+                day: 1,
+                line: 2,
+                column: 5,
+                ..
+            })
+        ));
     }
 }
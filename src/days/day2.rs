@@ -1,25 +1,37 @@
+use crate::common::answer::Answer;
 use crate::common::day::Day;
-use std::num::ParseIntError;
+use crate::common::error::AocError;
+use crate::common::input;
+use crate::common::registry::DayEntry;
 pub struct Day2 {
     pub input: String,
 }
 
+pub const REGISTRATION: DayEntry = DayEntry {
+    number: 2,
+    title: "Red-Nosed Reports",
+    create: |input| Box::new(Day2 { input }),
+};
+
 impl Day for Day2 {
-    fn part1(&self) -> Result<String, Box<dyn std::error::Error>> {
+    fn parse(&self) -> Result<(), AocError> {
+        self.reports()?;
+        Ok(())
+    }
+
+    fn part1(&self) -> Result<Answer, AocError> {
         let mut safety_score = 0;
-        for numbers in self.create_lines_as_vec_iter() {
-            let numbers = numbers?;
-            if check_vector_is_ok(&numbers)? {
+        for numbers in self.reports()? {
+            if check_vector_is_ok(&numbers) {
                 safety_score += 1
             }
         }
-        Ok(safety_score.to_string())
+        Ok(safety_score.into())
     }
 
-    fn part2(&self) -> Result<String, Box<dyn std::error::Error>> {
+    fn part2(&self) -> Result<Answer, AocError> {
         let mut safety_score = 0;
-        for numbers in self.create_lines_as_vec_iter() {
-            let numbers = numbers?;
+        for numbers in self.reports()? {
             let mut dampened: Vec<i32> = Vec::with_capacity(numbers.len() - 1);
             for to_remove in 0..numbers.len() {
                 dampened.clear();
@@ -31,46 +43,40 @@ impl Day for Day2 {
                     }
                 }));
 
-                if check_vector_is_ok(&dampened)? {
+                if check_vector_is_ok(&dampened) {
                     safety_score += 1;
                     break;
                 }
             }
         }
-        Ok(safety_score.to_string())
+        Ok(safety_score.into())
     }
 }
 
 impl Day2 {
-    fn create_lines_as_vec_iter(
-        &self,
-    ) -> impl Iterator<Item = Result<Vec<i32>, ParseIntError>> + '_ {
-        self.input.lines().map(|line| {
-            line.split_ascii_whitespace()
-                .map(|s| s.parse::<i32>())
-                .collect::<Result<Vec<_>, _>>()
-        })
+    fn reports(&self) -> Result<Vec<Vec<i32>>, AocError> {
+        input::ints_per_line(input::lines(&self.input)).map_err(|e| e.for_day(2))
     }
 }
 
-fn check_vector_is_ok(numbers: &[i32]) -> Result<bool, Box<dyn std::error::Error>> {
+fn check_vector_is_ok(numbers: &[i32]) -> bool {
     if numbers.len() < 2 {
         //Always true
-        return Ok(true);
+        return true;
     }
     let ascending = (numbers[0] - numbers[1]).is_negative();
     for el in 1..numbers.len() {
         let diff = numbers[el - 1] - numbers[el];
         if diff.abs() > 3 || diff.abs() < 1 {
-            return Ok(false);
+            return false;
         }
         match ascending {
             true if diff.is_negative() => {}
             false if diff.is_positive() => {}
-            _ => return Ok(false),
+            _ => return false,
         }
     }
-    Ok(true)
+    true
 }
 
 #[cfg(test)]
@@ -89,7 +95,7 @@ mod tests {
 "
             .to_string(),
         };
-        assert_eq!(day.part1().unwrap().trim(), "2");
+        assert_eq!(day.part1().unwrap(), Answer::Integer(2));
     }
     #[test]
     fn part2_example() {
@@ -102,6 +108,16 @@ mod tests {
 1 3 6 7 9"
                 .to_string(),
         };
-        assert_eq!(day.part2().unwrap().trim(), "4");
+        assert_eq!(day.part2().unwrap(), Answer::Integer(4));
+    }
+    #[test]
+    fn malformed_line() {
+        let day = Day2 {
+            input: "7 6 4 2 1\n1 2 ? 8 9".to_string(),
+        };
+        assert!(matches!(
+            day.part1(),
+            Err(AocError::MalformedLine { day: 2, line: 2, column: 5, ref text, .. }) if text == "?"
+        ));
     }
 }
@@ -1,184 +1,101 @@
+use crate::common::answer::Answer;
 use crate::common::day::Day;
-use std::error::Error;
+use crate::common::error::AocError;
+use crate::common::grid::Grid;
+use crate::common::registry::DayEntry;
+use crate::common::search::{self, Stencil, StencilMatch, WordMatch};
+use std::collections::HashSet;
 
 pub struct Day4 {
     pub input: String,
 }
 
+pub const REGISTRATION: DayEntry = DayEntry {
+    number: 4,
+    title: "Ceres Search",
+    create: |input| Box::new(Day4 { input }),
+};
+
 impl Day for Day4 {
-    fn part1(&self) -> Result<String, Box<dyn std::error::Error>> {
+    fn parse(&self) -> Result<(), AocError> {
+        XmasParser::from(&self.input)?;
+        Ok(())
+    }
+
+    fn part1(&self) -> Result<Answer, AocError> {
         let parser = XmasParser::from(&self.input)?;
-        let mut xmas_count = 0;
-        for direction in ParseDirection::VALUES {
-            for i in 0..parser.len() {
-                if parser.is_xmas(i, &direction) {
-                    xmas_count += 1;
-                }
-            }
-        }
+        Ok(parser.xmas_matches().len().into())
+    }
 
-        Ok(xmas_count.to_string())
+    fn part2(&self) -> Result<Answer, AocError> {
+        let parser = XmasParser::from(&self.input)?;
+        Ok(parser.cross_mas_matches().len().into())
     }
 
-    fn part2(&self) -> Result<String, Box<dyn std::error::Error>> {
+    fn explain(&self) -> Result<Vec<String>, AocError> {
         let parser = XmasParser::from(&self.input)?;
-        let mut xmas_count = 0;
-        for i in 0..parser.len() {
-            if parser.is_cross_mas(i) {
-                xmas_count += 1;
+        let words = parser.xmas_matches().into_iter().map(|m| {
+            format!(
+                "XMAS at {},{} facing {:?}",
+                m.start.x, m.start.y, m.direction
+            )
+        });
+        let crosses = parser
+            .cross_mas_matches()
+            .into_iter()
+            .map(|m| format!("X-MAS centred at {},{}", m.centre.x, m.centre.y));
+        Ok(words.chain(crosses).collect())
+    }
+
+    /// Part 1 and part 2 with unmatched letters as `.`, the animation adds one XMAS per frame
+    fn render(&self, frames: usize) -> Result<Vec<String>, AocError> {
+        let parser = XmasParser::from(&self.input)?;
+        let mut pictures = Vec::new();
+        let mut words = HashSet::new();
+        for m in parser.xmas_matches() {
+            words.extend(m.cells());
+            if pictures.len() < frames {
+                pictures.push(search::highlight(&parser.grid, &words));
             }
         }
-        Ok(xmas_count.to_string())
+        let crosses = parser
+            .cross_mas_matches()
+            .into_iter()
+            .flat_map(|m| m.cells)
+            .collect();
+        pictures.push(format!(
+            "{}\n\n{}",
+            search::highlight(&parser.grid, &words),
+            search::highlight(&parser.grid, &crosses)
+        ));
+        Ok(pictures)
     }
 }
 
-enum ParseDirection {
-    LeftToRight,
-    RightToLeft,
-    TopToBottom,
-    BottomToTop,
-    LeftTopToRightBottom,
-    RightTopToLeftBottom,
-    LeftBottomToRightTop,
-    RightBottomToLeftTop,
-}
-impl ParseDirection {
-    const VALUES: [Self; 8] = [
-        Self::LeftToRight,
-        Self::RightToLeft,
-        Self::TopToBottom,
-        Self::BottomToTop,
-        Self::LeftTopToRightBottom,
-        Self::RightTopToLeftBottom,
-        Self::LeftBottomToRightTop,
-        Self::RightBottomToLeftTop,
-    ];
+struct XmasParser {
+    grid: Grid<char>,
 }
 
-struct XmasParser {
-    input: Vec<char>,
-    width: i32,
+/// Two MAS crossing on their A, each may be written backwards
+fn cross_mas() -> Stencil {
+    Stencil::parse("M.S\n.A.\nM.S").expect("the X-MAS stencil is a valid grid")
 }
 
 impl XmasParser {
-    fn from(input: &str) -> Result<Self, Box<dyn Error>> {
-        let width = input
-            .lines()
-            .next()
-            .ok_or::<String>("Received empty input".into())?
-            .len();
-        let input: Vec<char> = input.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+    fn from(input: &str) -> Result<Self, AocError> {
         Ok(XmasParser {
-            input,
-            width: width as i32,
+            grid: Grid::chars(input).map_err(|e| e.for_day(4))?,
         })
     }
 
-    fn len(&self) -> usize {
-        self.input.len()
+    /// Every XMAS as its first letter and reading direction
+    fn xmas_matches(&self) -> Vec<WordMatch> {
+        search::find_word(&self.grid, "XMAS")
     }
 
-    fn is_cross_mas(&self, pos: usize) -> bool {
-        if self.input[pos] != 'A' {
-            return false;
-        }
-        let pos = pos as i32;
-        if (pos % self.width) - 1 < 0 || (pos % self.width) + 1 >= self.width {
-            return false;
-        }
-        let cross = (
-            pos - self.width - 1,
-            pos - self.width + 1,
-            pos + self.width - 1,
-            pos + self.width + 1,
-        );
-        matches!(
-            self.get_chars_2(cross),
-            (Some('M'), Some('M'), Some('S'), Some('S'))
-                | (Some('M'), Some('S'), Some('M'), Some('S'))
-                | (Some('S'), Some('S'), Some('M'), Some('M'))
-                | (Some('S'), Some('M'), Some('S'), Some('M'))
-        )
-    }
-
-    fn is_xmas(&self, pos: usize, direction: &ParseDirection) -> bool {
-        if self.input[pos] != 'X' {
-            return false;
-        }
-        let pos = pos as i32;
-        let maybe_mas = match direction {
-            ParseDirection::LeftToRight if (pos % self.width) + 3 <= self.width - 1 => {
-                (pos + 1, pos + 2, pos + 3)
-            }
-            ParseDirection::RightToLeft if (pos % self.width) - 3 >= 0 => {
-                (pos - 1, pos - 2, pos - 3)
-            }
-            ParseDirection::TopToBottom => (
-                pos + self.width,
-                pos + (2 * self.width),
-                pos + (3 * self.width),
-            ),
-            ParseDirection::BottomToTop => (
-                pos - self.width,
-                pos - (2 * self.width),
-                pos - (3 * self.width),
-            ),
-            ParseDirection::LeftTopToRightBottom if (pos % self.width) + 3 <= self.width - 1 => (
-                pos + self.width + 1,
-                pos + (2 * self.width) + 2,
-                pos + (3 * self.width) + 3,
-            ),
-            ParseDirection::RightTopToLeftBottom if (pos % self.width) - 3 >= 0 => (
-                pos + self.width - 1,
-                pos + (2 * self.width) - 2,
-                pos + (3 * self.width) - 3,
-            ),
-            ParseDirection::LeftBottomToRightTop if (pos % self.width) + 3 <= self.width - 1 => (
-                pos - self.width + 1,
-                pos - (2 * self.width) + 2,
-                pos - (3 * self.width) + 3,
-            ),
-            ParseDirection::RightBottomToLeftTop if (pos % self.width) - 3 >= 0 => (
-                pos - self.width - 1,
-                pos - (2 * self.width) - 2,
-                pos - (3 * self.width) - 3,
-            ),
-            _ => return false,
-        };
-
-        match self.get_chars(maybe_mas) {
-            (Some('M'), Some('A'), Some('S')) => true,
-            (_, _, _) => false,
-        }
-    }
-    fn get_chars(&self, train: (i32, i32, i32)) -> (Option<&char>, Option<&char>, Option<&char>) {
-        if train.0.is_negative() || train.1.is_negative() || train.2.is_negative() {
-            return (None, None, None);
-        }
-        (
-            self.input.get(train.0 as usize),
-            self.input.get(train.1 as usize),
-            self.input.get(train.2 as usize),
-        )
-    }
-
-    fn get_chars_2(
-        &self,
-        train: (i32, i32, i32, i32),
-    ) -> (Option<&char>, Option<&char>, Option<&char>, Option<&char>) {
-        if train.0.is_negative()
-            || train.1.is_negative()
-            || train.2.is_negative()
-            || train.3.is_negative()
-        {
-            return (None, None, None, None);
-        }
-        (
-            self.input.get(train.0 as usize),
-            self.input.get(train.1 as usize),
-            self.input.get(train.2 as usize),
-            self.input.get(train.3 as usize),
-        )
+    /// Every X-MAS, centred on its A
+    fn cross_mas_matches(&self) -> Vec<StencilMatch> {
+        search::find_stencil(&self.grid, &cross_mas())
     }
 }
 
@@ -186,10 +103,7 @@ impl XmasParser {
 mod tests {
     use super::*;
 
-    #[test]
-    fn part1_example() {
-        let day = Day4 {
-            input: "MMMSXXMASM
+    const EXAMPLE: &str = "MMMSXXMASM
 MSAMXMSMSA
 AMXSXMAAMM
 MSAMASMSMX
@@ -198,26 +112,59 @@ XXAMMXXAMA
 SMSMSASXSS
 SAXAMASAAA
 MAMMMXMMMM
-MXMXAXMASX"
-                .to_string(),
+MXMXAXMASX";
+
+    #[test]
+    fn part1_example() {
+        let day = Day4 {
+            input: EXAMPLE.to_string(),
         };
-        assert_eq!(day.part1().unwrap().trim(), "18");
+        assert_eq!(day.part1().unwrap(), Answer::Integer(18));
     }
     #[test]
     fn part2_example() {
         let day = Day4 {
-            input: "MMMSXXMASM
-MSAMXMSMSA
-AMXSXMAAMM
-MSAMASMSMX
-XMASAMXAMM
-XXAMMXXAMA
-SMSMSASXSS
-SAXAMASAAA
-MAMMMXMMMM
-MXMXAXMASX"
-                .to_string(),
+            input: EXAMPLE.to_string(),
+        };
+        assert_eq!(day.part2().unwrap(), Answer::Integer(9));
+    }
+    #[test]
+    fn render_matches() {
+        let day = Day4 {
+            input: EXAMPLE.to_string(),
         };
-        assert_eq!(day.part2().unwrap().trim(), "9");
+        let pictures = day.render(1).unwrap();
+        assert_eq!(pictures.len(), 2);
+        assert_eq!(
+            pictures[0].lines().take(2).collect::<Vec<_>>(),
+            vec!["....X.....", ".....M...."]
+        );
+        assert_eq!(
+            pictures[1],
+            "....XXMAS.
+.SAMXMS...
+...S..A...
+..A.A.MS.X
+XMASAMX.MM
+X.....XA.A
+S.S.S.S.SS
+.A.A.A.A.A
+..M.M.M.MM
+.X.X.XMASX
+
+.M.S......
+..A..MSMS.
+.M.S.MAA..
+..A.ASMSM.
+.M.S.M....
+..........
+S.S.S.S.S.
+.A.A.A.A..
+M.M.M.M.M.
+.........."
+        );
+        let explained = day.explain().unwrap();
+        assert_eq!(explained[0], "XMAS at 4,0 facing SouthEast");
+        assert_eq!(explained[18], "X-MAS centred at 2,1");
     }
 }
@@ -1,85 +1,81 @@
+use crate::common::answer::Answer;
 use crate::common::day::Day;
+use crate::common::error::AocError;
+use crate::common::graph;
+use crate::common::input::{self, Line};
+use crate::common::registry::DayEntry;
 use std::collections::HashMap;
 pub struct Day5 {
     pub input: String,
 }
 
+pub const REGISTRATION: DayEntry = DayEntry {
+    number: 5,
+    title: "Print Queue",
+    create: |input| Box::new(Day5 { input }),
+};
+
 impl Day for Day5 {
-    fn part1(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let mut split = self.input.split("\n\n");
+    fn parse(&self) -> Result<(), AocError> {
+        let (raw_rules, raw_updates) = self.split_input()?;
+        RuleEngine::from(raw_rules)?;
+        get_updates(raw_updates)?;
+        Ok(())
+    }
+
+    fn part1(&self) -> Result<Answer, AocError> {
+        let (raw_rules, raw_updates) = self.split_input()?;
         let mut sum = 0;
-        let rule_engine = RuleEngine::from(split.next().ok_or("Did not find filter list")?)?;
-        for update_line in self
-            .get_updates(split.next().ok_or("Did not find update list")?)?
-            .iter()
-        {
+        let rule_engine = RuleEngine::from(raw_rules)?;
+        for update_line in get_updates(raw_updates)?.iter() {
             if rule_engine.is_valid(update_line) {
                 sum += update_line[(update_line.len() - 1) / 2];
             }
         }
 
-        Ok(sum.to_string())
+        Ok(sum.into())
     }
 
-    fn part2(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let mut split = self.input.split("\n\n");
+    fn part2(&self) -> Result<Answer, AocError> {
+        let (raw_rules, raw_updates) = self.split_input()?;
         let mut sum = 0;
-        let rule_engine = RuleEngine::from(split.next().ok_or("Did not find filter list")?)?;
-        for update_line in self
-            .get_updates(split.next().ok_or("Did not find update list")?)?
-            .iter_mut()
-        {
+        let rule_engine = RuleEngine::from(raw_rules)?;
+        for update_line in get_updates(raw_updates)?.iter_mut() {
             if !rule_engine.is_valid(update_line) {
-                rule_engine.apply(update_line);
+                rule_engine.apply(update_line)?;
                 sum += update_line[(update_line.len() - 1) / 2];
             }
         }
 
-        Ok(sum.to_string())
+        Ok(sum.into())
     }
 }
 
 impl Day5 {
-    fn get_updates(&self, raw_updates: &str) -> Result<Vec<Vec<i32>>, Box<dyn std::error::Error>> {
-        let lines = raw_updates
-            .lines()
-            .map(|l| {
-                let update_line = l
-                    .trim()
-                    .split(',')
-                    .map(|s| {
-                        let update = s.parse::<i32>()?;
-                        Ok::<_, Box<dyn std::error::Error>>(update)
-                    })
-                    .collect::<Result<Vec<i32>, _>>()?;
-                Ok::<_, Box<dyn std::error::Error>>(update_line)
-            })
-            .collect::<Result<Vec<Vec<i32>>, _>>();
-        lines
+    fn split_input(&self) -> Result<(Vec<Line<'_>>, Vec<Line<'_>>), AocError> {
+        let mut sections = input::sections(&self.input).into_iter();
+        let raw_rules = sections
+            .next()
+            .ok_or_else(|| AocError::MissingInput("Did not find filter list".into()))?;
+        let raw_updates = sections
+            .next()
+            .ok_or_else(|| AocError::MissingInput("Did not find update list".into()))?;
+        Ok((raw_rules, raw_updates))
     }
 }
+
+fn get_updates(raw_updates: Vec<Line>) -> Result<Vec<Vec<i32>>, AocError> {
+    input::ints_per_line(raw_updates).map_err(|e| e.for_day(5))
+}
+
 struct RuleEngine {
     rules: HashMap<i32, Vec<i32>>,
 }
 
 impl RuleEngine {
-    fn from(input: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let parsed_rules = input
-            .lines()
-            .map(|l| {
-                let mut split = l.trim().split('|');
-                let split1 = split
-                    .next()
-                    .ok_or::<String>("Encountered wrong rule input".into())?
-                    .parse::<i32>()?;
-                let split2 = split
-                    .next()
-                    .ok_or::<String>("Encountered wrong rule input".into())?
-                    .parse::<i32>()?;
-
-                Ok::<(i32, i32), Box<dyn std::error::Error>>((split1, split2))
-            })
-            .collect::<Result<Vec<(i32, i32)>, _>>()?;
+    fn from(input: Vec<Line>) -> Result<Self, AocError> {
+        let parsed_rules =
+            input::pairs_separated_by::<i32>(input, "|").map_err(|e| e.for_day(5))?;
 
         let mut rules = HashMap::new();
         for rule in parsed_rules {
@@ -103,21 +99,13 @@ impl RuleEngine {
         true
     }
 
-    fn apply(&self, input: &mut [i32]) {
-        input.sort_by(|a, b| {
-            if let Some(rules) = self.rules.get(a) {
-                if rules.contains(b) {
-                    return std::cmp::Ordering::Less;
-                }
-            }
-            if let Some(rules) = self.rules.get(b) {
-                if rules.contains(a) {
-                    return std::cmp::Ordering::Greater;
-                }
-            }
-
-            std::cmp::Ordering::Equal
-        });
+    fn apply(&self, input: &mut [i32]) -> Result<(), AocError> {
+        let order = graph::toposort(input, &self.rules).map_err(|cycle| AocError::Solver {
+            day: 5,
+            reason: format!("rules for update {:?} are cyclic: {}", input, cycle),
+        })?;
+        input.copy_from_slice(&order);
+        Ok(())
     }
 }
 
@@ -158,7 +146,7 @@ mod tests {
             97,13,75,29,47"
                 .to_string(),
         };
-        assert_eq!(day.part1().unwrap().trim(), "143");
+        assert_eq!(day.part1().unwrap(), Answer::Integer(143));
     }
     #[test]
     fn part2_example() {
@@ -193,6 +181,58 @@ mod tests {
             97,13,75,29,47"
                 .to_string(),
         };
-        assert_eq!(day.part2().unwrap().trim(), "123");
+        assert_eq!(day.part2().unwrap(), Answer::Integer(123));
+    }
+    #[test]
+    fn malformed_rule() {
+        let day = Day5 {
+            input: "47|53\n97-13\n\n75,47".to_string(),
+        };
+        assert!(matches!(
+            day.part1(),
+            Err(AocError::MalformedLine { day: 5, line: 2, column: 1, .. })
+        ));
+    }
+    #[test]
+    fn malformed_update() {
+        let day = Day5 {
+            input: "47|53\n97|13\n\n75,47\n97,x,13".to_string(),
+        };
+        assert!(matches!(
+            day.part1(),
+            Err(AocError::MalformedLine { day: 5, line: 5, column: 4, ref text, .. }) if text == "x"
+        ));
+    }
+    #[test]
+    fn empty_field_in_update() {
+        let day = Day5 {
+            input: "47|53\n\n,".to_string(),
+        };
+        assert!(matches!(
+            day.part1(),
+            Err(AocError::MalformedLine { day: 5, line: 3, column: 1, ref reason, .. })
+                if reason == "empty field"
+        ));
+        let day = Day5 {
+            input: "47|53\n\n75,,47".to_string(),
+        };
+        assert!(matches!(
+            day.part2(),
+            Err(AocError::MalformedLine { day: 5, line: 3, column: 4, .. })
+        ));
+    }
+    #[test]
+    fn missing_updates() {
+        let day = Day5 {
+            input: "47|53".to_string(),
+        };
+        assert!(matches!(day.part1(), Err(AocError::MissingInput(_))));
+    }
+    #[test]
+    fn cyclic_rules() {
+        let day = Day5 {
+            input: "1|2\n2|3\n3|1\n\n1,3,2".to_string(),
+        };
+        assert!(matches!(day.part2(), Err(AocError::Solver { day: 5, .. })));
     }
 }
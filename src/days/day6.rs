@@ -1,197 +1,707 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
+use crate::common::answer::Answer;
+use crate::common::cycle;
 use crate::common::day::Day;
+use crate::common::error::AocError;
+use crate::common::geom::{Coord, Direction};
+use crate::common::grid::{Grid, GridError};
+use crate::common::pool::{self, Task};
+use crate::common::registry::DayEntry;
 pub struct Day6 {
     pub input: String,
+    /// Threads for the loop obstacle search
+    pub jobs: usize,
 }
 
+pub const REGISTRATION: DayEntry = DayEntry {
+    number: 6,
+    title: "Guard Gallivant",
+    create: |input| Box::new(Day6 { input, jobs: 1 }),
+};
+
 impl Day for Day6 {
-    fn part1(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let mut world = World::from(&self.input);
-        let mut visited_coords: HashSet<Coord> = HashSet::new();
-        visited_coords.insert(world.player.coord);
-        loop {
-            match world.move_player() {
-                PlayerMoveResult::Location(player) => _ = visited_coords.insert(player.coord),
-                PlayerMoveResult::Turned(_) => {}
-                PlayerMoveResult::FellOfWorld => {
-                    break;
-                }
-            }
-        }
-        Ok(visited_coords.len().to_string())
+    fn parse(&self) -> Result<(), AocError> {
+        World::from(&self.input)?;
+        Ok(())
     }
 
-    fn part2(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let mut loops_detected = 0;
-        let mut index_to_insert = 0;
-        loop {
-            let mut world = World::from(&self.input);
-            if world.spawn_object(index_to_insert) == SpawnResult::OutOfBounds {
-                break;
-            }
+    /// Cells visited by any of the guards
+    fn part1(&self) -> Result<Answer, AocError> {
+        let world = World::from(&self.input)?;
+        let patrol = world.patrol();
+        let visited_coords = patrol.visited.iter().flatten().collect::<HashSet<_>>();
+        Ok(visited_coords.len().into())
+    }
 
-            let mut visited_coords: HashSet<Player> = HashSet::new();
-            visited_coords.insert(world.player);
-            while let PlayerMoveResult::Location(player) | PlayerMoveResult::Turned(player) =
-                world.move_player()
-            {
-                if !visited_coords.insert(player) {
-                    loops_detected += 1;
-                    break;
-                }
-            }
+    fn part2(&self) -> Result<Answer, AocError> {
+        let world = World::from(&self.input)?;
+        let guard = world.guard()?;
+        Ok(world.loop_obstacles(guard, self.jobs).len().into())
+    }
+
+    fn set_jobs(&mut self, jobs: usize) {
+        self.jobs = jobs;
+    }
 
-            index_to_insert += 1;
+    /// The loop obstacles for a single guard, the patrol and its collisions for several
+    fn explain(&self) -> Result<Vec<String>, AocError> {
+        let world = World::from(&self.input)?;
+        if let [guard] = world.guards[..] {
+            return Ok(world
+                .explain_loops(guard, self.jobs)
+                .into_iter()
+                .map(|o| {
+                    format!(
+                        "obstacle {},{}: loop of {} steps entered at {},{} facing {:?}",
+                        o.coord.x,
+                        o.coord.y,
+                        o.length,
+                        o.entry.coord.x,
+                        o.entry.coord.y,
+                        o.entry.dir
+                    )
+                })
+                .collect());
         }
-        Ok(loops_detected.to_string())
+        let patrol = world.patrol();
+        let guards = world.guards.iter().zip(&patrol.visited).enumerate();
+        let mut lines = guards
+            .map(|(i, (guard, visited))| {
+                format!(
+                    "guard {} from {},{} facing {:?}: visits {} cells",
+                    i + 1,
+                    guard.coord.x,
+                    guard.coord.y,
+                    guard.dir,
+                    visited.len()
+                )
+            })
+            .collect::<Vec<_>>();
+        lines.extend(patrol.collisions.iter().map(|c| {
+            format!(
+                "collision of guards {} and {} at {},{} after {} moves",
+                c.guards.0 + 1,
+                c.guards.1 + 1,
+                c.coord.x,
+                c.coord.y,
+                c.tick
+            )
+        }));
+        Ok(lines)
+    }
+
+    fn render(&self, frames: usize) -> Result<Vec<String>, AocError> {
+        let world = World::from(&self.input)?;
+        let guard = world.guard()?;
+        let mut trail = HashMap::new();
+        let mut pictures = Vec::new();
+        let mut seen = HashSet::from([guard]);
+        let mut player = guard;
+        // The final picture needs the whole trail, so the walk goes on after the last frame
+        // until the guard leaves or repeats a state
+        while let Some(next) = world.step(player).filter(|next| seen.insert(*next)) {
+            if pictures.len() < frames {
+                pictures.push(world.draw(&trail, &HashSet::new(), player));
+            }
+            mark(&mut trail, player, next);
+            player = next;
+        }
+        let loop_obstacles = world
+            .loop_obstacles(guard, self.jobs)
+            .into_iter()
+            .collect();
+        pictures.push(world.draw(&trail, &loop_obstacles, guard));
+        Ok(pictures)
     }
 }
 
-#[derive(Debug)]
-enum Position {
+/// The map legend: `.` free, `#` obstacle, `N` `E` `S` `W` one-way tiles, digits for pairs of
+/// teleporters and `X` for walls that end the walk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Position {
     Free,
     Obstacle,
+    /// Can only be entered walking in its direction, from anywhere else it blocks like an
+    /// obstacle
+    OneWay(Direction),
+    /// Walking onto it moves the guard to the other teleporter with the same label, keeping
+    /// their direction
+    Teleporter(char),
+    /// Walking into it ends the walk, like leaving the map
+    Exit,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum FaceDirection {
-    Up,
+impl Position {
+    fn from_glyph(glyph: char) -> Option<Self> {
+        Some(match glyph {
+            '.' => Position::Free,
+            '#' => Position::Obstacle,
+            'N' => Position::OneWay(Direction::North),
+            'E' => Position::OneWay(Direction::East),
+            'S' => Position::OneWay(Direction::South),
+            'W' => Position::OneWay(Direction::West),
+            '0'..='9' => Position::Teleporter(glyph),
+            'X' => Position::Exit,
+            _ => return None,
+        })
+    }
+
+    fn glyph(self) -> char {
+        match self {
+            Position::Free => '.',
+            Position::Obstacle => '#',
+            Position::OneWay(Direction::North) => 'N',
+            Position::OneWay(Direction::East) => 'E',
+            Position::OneWay(Direction::South) => 'S',
+            Position::OneWay(_) => 'W',
+            Position::Teleporter(label) => label,
+            Position::Exit => 'X',
+        }
+    }
+}
+
+/// What the guard does when something blocks their way
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Turn {
+    #[default]
     Right,
-    Down,
     Left,
+    Reverse,
+}
+
+impl Turn {
+    fn apply(self, dir: Direction) -> Direction {
+        match self {
+            Turn::Right => dir.turn_right(),
+            Turn::Left => dir.turn_left(),
+            Turn::Reverse => dir.opposite(),
+        }
+    }
+}
+
+/// What happens when the guard walks over the edge of the map
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Edges {
+    /// The walk ends, as in the puzzle
+    #[default]
+    Exit,
+    /// The guard comes back in on the opposite side
+    Wrap,
+}
+
+/// How the guard walks, the default is the puzzle's
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    pub turn: Turn,
+    pub edges: Edges,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Player {
+pub struct Player {
+    pub coord: Coord,
+    pub dir: Direction,
+}
+
+/// An added obstacle that traps the guard, with the loop they end up walking
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LoopObstacle {
     coord: Coord,
-    dir: FaceDirection,
+    /// Steps to walk the loop once, turns count as a step
+    length: usize,
+    /// The first state on the loop
+    entry: Player,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Coord {
-    x: i32,
-    y: i32,
+/// Two guards that end a move on the same cell, or pass each other by swapping cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Collision {
+    /// Moves every guard made so far
+    tick: usize,
+    /// The shared cell, or the cell the first guard moved into for a swap
+    coord: Coord,
+    /// Indexes into `World::guards`
+    guards: (usize, usize),
+}
+
+struct Patrol {
+    /// The cells each guard walked on, in the order of `World::guards`
+    visited: Vec<HashSet<Coord>>,
+    collisions: Vec<Collision>,
 }
 
-#[derive(Debug)]
-struct World {
-    space: Vec<Vec<Position>>,
-    player: Player,
+/// Which ways the guard walked through a cell
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Trail {
+    vertical: bool,
+    horizontal: bool,
 }
 
-enum PlayerMoveResult {
-    Location(Player),
-    Turned(Player),
-    FellOfWorld,
+/// Adds the move from `from` to `to` to the trail, turning marks the cell both ways
+fn mark(trail: &mut HashMap<Coord, Trail>, from: Player, to: Player) {
+    let cells = if from.coord == to.coord {
+        vec![(from.coord, from.dir), (from.coord, to.dir)]
+    } else {
+        vec![(from.coord, from.dir), (to.coord, to.dir)]
+    };
+    for (coord, dir) in cells {
+        let cell = trail.entry(coord).or_default();
+        match dir {
+            Direction::North | Direction::South => cell.vertical = true,
+            _ => cell.horizontal = true,
+        }
+    }
 }
 
-#[derive(PartialEq)]
-enum SpawnResult {
-    Ok,
-    OutOfBounds,
+/// Reasons the input is not a map the guard can walk on, lines and columns are 1-based
+#[derive(Debug, PartialEq)]
+pub enum WorldError {
+    /// Empty input, ragged rows and unknown glyphs
+    Grid(GridError),
+    MissingGuard,
+    /// The obstacle search needs a single guard, `(line, column)` of the first two
+    DuplicateGuard {
+        first: (usize, usize),
+        second: (usize, usize),
+    },
+    /// Teleporters come in pairs, this one has no partner or more than one
+    UnpairedTeleporter {
+        line: usize,
+        column: usize,
+        label: char,
+    },
 }
 
-impl Player {
-    fn set_pos(&mut self, x: usize, y: usize) {
-        self.coord.x = x as i32;
-        self.coord.y = y as i32;
+impl From<WorldError> for AocError {
+    fn from(error: WorldError) -> Self {
+        match error {
+            WorldError::Grid(error) => error.for_day(6),
+            WorldError::MissingGuard => {
+                AocError::MissingInput("Day 6 map has no guard, expected one of ^ > v <".into())
+            }
+            WorldError::DuplicateGuard { first, second } => AocError::MalformedLine {
+                day: 6,
+                line: second.0,
+                column: second.1,
+                text: "guard".into(),
+                reason: format!(
+                    "second guard, the first is at line {}, column {}",
+                    first.0, first.1
+                ),
+            },
+            WorldError::UnpairedTeleporter {
+                line,
+                column,
+                label,
+            } => AocError::MalformedLine {
+                day: 6,
+                line,
+                column,
+                text: label.to_string(),
+                reason: "teleporters have to come in pairs".into(),
+            },
+        }
     }
 }
 
+/// Clones share the parsed map, only the obstacle added on top of it is per clone
+#[derive(Debug, Clone)]
+pub struct World {
+    space: Arc<Grid<Position>>,
+    obstacle: Option<Coord>,
+    /// In reading order of the map
+    guards: Vec<Player>,
+    /// 0-based line of the input with the top row, blank lines before the map are skipped
+    first_line: usize,
+    /// Every teleporter and the one it leads to
+    teleports: Arc<HashMap<Coord, Coord>>,
+    rules: Rules,
+}
+
 impl World {
-    fn from(input: &str) -> Self {
-        let mut player = Player {
-            coord: Coord { x: -1, y: -1 },
-            dir: FaceDirection::Up,
-        };
-        let rows = input.lines().count();
-        let cols = input
-            .lines()
-            .next()
-            .expect("Expected at least one line")
-            .chars()
-            .count();
-
-        let mut space = Vec::with_capacity(rows);
-        for _ in 0..rows {
-            let mut vec = Vec::with_capacity(cols);
-            for _ in 0..cols {
-                vec.push(Position::Free);
+    pub fn from(input: &str) -> Result<Self, WorldError> {
+        let mut guards = Vec::new();
+        let space = Grid::parse(input, |coord, char| {
+            let dir = match char {
+                '^' => Direction::North,
+                '>' => Direction::East,
+                'v' => Direction::South,
+                '<' => Direction::West,
+                _ => {
+                    return Position::from_glyph(char)
+                        .ok_or("unknown glyph, expected a guard ^ > v < or one of . # N E S W X 0-9")
+                }
+            };
+            guards.push(Player { coord, dir });
+            Ok(Position::Free)
+        })
+        .map_err(WorldError::Grid)?;
+        if guards.is_empty() {
+            return Err(WorldError::MissingGuard);
+        }
+        let first_line = input.lines().take_while(|l| l.trim().is_empty()).count();
+
+        let mut teleporters: HashMap<char, Vec<Coord>> = HashMap::new();
+        for (coord, position) in space.iter() {
+            if let Position::Teleporter(label) = position {
+                teleporters.entry(*label).or_default().push(coord);
+            }
+        }
+        let mut teleports = HashMap::new();
+        for (label, coords) in teleporters {
+            match coords[..] {
+                [a, b] => {
+                    teleports.insert(a, b);
+                    teleports.insert(b, a);
+                }
+                _ => {
+                    let coord = coords[coords.len().min(3) - 1];
+                    return Err(WorldError::UnpairedTeleporter {
+                        line: first_line + coord.y as usize + 1,
+                        column: coord.x as usize + 1,
+                        label,
+                    });
+                }
             }
-            space.push(vec);
-        }
-        for (i, line) in input.lines().enumerate() {
-            for (j, char) in line.chars().enumerate() {
-                match char {
-                    '#' => space[i][j] = Position::Obstacle,
-                    '^' => {
-                        player.set_pos(j, i);
+        }
+
+        Ok(World {
+            guards,
+            space: Arc::new(space),
+            obstacle: None,
+            first_line,
+            teleports: Arc::new(teleports),
+            rules: Rules::default(),
+        })
+    }
+
+    pub fn guards(&self) -> &[Player] {
+        &self.guards
+    }
+
+    pub fn with_rules(self, rules: Rules) -> Self {
+        World { rules, ..self }
+    }
+
+    /// The puzzle's rules on a map of free cells and obstacles, the jump table only knows these
+    fn is_classic(&self) -> bool {
+        self.rules == Rules::default()
+            && self
+                .space
+                .iter()
+                .all(|(_, p)| matches!(p, Position::Free | Position::Obstacle))
+    }
+
+    /// The only guard, the obstacle search is about a single one
+    fn guard(&self) -> Result<Player, WorldError> {
+        match self.guards[..] {
+            [guard] => Ok(guard),
+            [first, second, ..] => {
+                let position = |coord: Coord| {
+                    (
+                        self.first_line + coord.y as usize + 1,
+                        coord.x as usize + 1,
+                    )
+                };
+                Err(WorldError::DuplicateGuard {
+                    first: position(first.coord),
+                    second: position(second.coord),
+                })
+            }
+            [] => Err(WorldError::MissingGuard),
+        }
+    }
+
+    /// Moves all guards at once until each has left the map or is walking a loop. Looping
+    /// guards keep moving, they can still run into the others.
+    fn patrol(&self) -> Patrol {
+        let mut guards = self.guards.iter().map(|&g| Some(g)).collect::<Vec<_>>();
+        let mut seen = self
+            .guards
+            .iter()
+            .map(|&g| HashSet::from([g]))
+            .collect::<Vec<_>>();
+        let mut visited = self
+            .guards
+            .iter()
+            .map(|g| HashSet::from([g.coord]))
+            .collect::<Vec<_>>();
+        let mut walking = vec![true; guards.len()];
+        let mut collisions = Vec::new();
+        let mut tick = 0;
+        while walking.iter().any(|&w| w) {
+            tick += 1;
+            let previous = guards.clone();
+            for (i, guard) in guards.iter_mut().enumerate() {
+                *guard = guard.and_then(|g| self.step(g));
+                match guard {
+                    Some(g) if walking[i] => {
+                        visited[i].insert(g.coord);
+                        walking[i] = seen[i].insert(*g);
                     }
-                    _ => {}
+                    Some(_) => {}
+                    None => walking[i] = false,
                 }
             }
+            for (i, a) in guards.iter().enumerate() {
+                for (j, b) in guards.iter().enumerate().skip(i + 1) {
+                    if let (Some(a), Some(b)) = (a, b) {
+                        let swapped = previous[i].is_some_and(|p| p.coord == b.coord)
+                            && previous[j].is_some_and(|p| p.coord == a.coord);
+                        if a.coord == b.coord || swapped {
+                            collisions.push(Collision {
+                                tick,
+                                coord: a.coord,
+                                guards: (i, j),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        Patrol {
+            visited,
+            collisions,
         }
-        World { player, space }
     }
-    fn spawn_object(&mut self, index: usize) -> SpawnResult {
-        let rows = self.space.len();
-        let cols = self.space[0].len();
 
-        if index >= rows * cols {
-            return SpawnResult::OutOfBounds;
+    fn with_obstacle(&self, coord: Coord) -> Self {
+        World {
+            obstacle: Some(coord),
+            ..self.clone()
         }
+    }
 
-        let row = index / cols;
-        let col = index % cols;
-        self.space[row][col] = Position::Obstacle;
-        SpawnResult::Ok
+    fn position(&self, coord: Coord) -> Option<Position> {
+        match self.obstacle {
+            Some(obstacle) if obstacle == coord => Some(Position::Obstacle),
+            _ => self.space.get(coord).copied(),
+        }
     }
 
-    fn move_player(&mut self) -> PlayerMoveResult {
-        let (dx, dy) = match self.player.dir {
-            FaceDirection::Up => (0, -1),
-            FaceDirection::Right => (1, 0),
-            FaceDirection::Down => (0, 1),
-            FaceDirection::Left => (-1, 0),
-        };
+    /// The candidates that trap the guard in a loop, in route order
+    fn loop_obstacles(&self, guard: Player, jobs: usize) -> Vec<Coord> {
+        self.trapping_candidates(guard, jobs)
+            .into_iter()
+            .map(|(_, obstacle)| obstacle)
+            .collect()
+    }
 
-        let next_tile = (self.player.coord.x + dx, self.player.coord.y + dy);
+    /// Every obstacle that traps the guard, ordered by row then column, with the loop it causes
+    /// walked step by step
+    fn explain_loops(&self, guard: Player, jobs: usize) -> Vec<LoopObstacle> {
+        let mut explained = self
+            .trapping_candidates(guard, jobs)
+            .into_iter()
+            .map(|(before, coord)| {
+                let blocked = self.with_obstacle(coord);
+                let cycle = cycle::hashed(before, |player| blocked.step(*player))
+                    .expect("the jump search found a loop");
+                LoopObstacle {
+                    coord,
+                    length: cycle.length,
+                    entry: cycle.entry,
+                }
+            })
+            .collect::<Vec<_>>();
+        explained.sort_by_key(|o| (o.coord.y, o.coord.x));
+        explained
+    }
 
-        if next_tile.0 < 0
-            || next_tile.1 < 0
-            || next_tile.0 as usize >= self.space[0].len()
-            || next_tile.1 as usize >= self.space.len()
-        {
-            return PlayerMoveResult::FellOfWorld;
+    /// Every candidate is its own simulation, so they are split evenly over `jobs` threads
+    fn trapping_candidates(&self, guard: Player, jobs: usize) -> Vec<(Player, Coord)> {
+        let jumps = self.is_classic().then(|| JumpTable::new(&self.space));
+        let candidates = self.candidates(guard);
+        let tasks = candidates
+            .chunks(candidates.len().div_ceil(jobs.max(1)).max(1))
+            .map(|chunk| {
+                let jumps = jumps.as_ref();
+                Box::new(move || {
+                    chunk
+                        .iter()
+                        .filter(|&&(before, obstacle)| {
+                            self.with_obstacle(obstacle).loops(before, jumps)
+                        })
+                        .copied()
+                        .collect()
+                }) as Task<Vec<(Player, Coord)>>
+            })
+            .collect();
+        pool::run(jobs, tasks).into_iter().flatten().collect()
+    }
+
+    /// Whether a guard starting as `player` ends up walking in circles. Without a jump table
+    /// the guard is walked one step at a time.
+    fn loops(&self, player: Player, jumps: Option<&JumpTable>) -> bool {
+        match jumps {
+            Some(jumps) => {
+                cycle::hashed(player, |player| jumps.jump(*player, self.obstacle)).is_some()
+            }
+            None => cycle::hashed(player, |player| self.step(*player)).is_some(),
         }
+    }
 
-        match self.space[next_tile.1 as usize][next_tile.0 as usize] {
-            Position::Free => {
-                self.player
-                    .set_pos(next_tile.0 as usize, next_tile.1 as usize);
-                PlayerMoveResult::Location(self.player)
+    /// Every free cell on the guard's route where a new obstacle could go, with the state right
+    /// before the guard first walks into it. Putting the obstacle anywhere else doesn't change
+    /// the route, and the route up to that state stays the same, so the search can start there.
+    /// Cells first reached through a teleporter are skipped, the guard would land on the
+    /// obstacle. The walk stops when the guard leaves the map or repeats a state.
+    fn candidates(&self, guard: Player) -> Vec<(Player, Coord)> {
+        let mut visited_coords = HashSet::from([guard.coord]);
+        let mut seen = HashSet::from([guard]);
+        let mut candidates = Vec::new();
+        let mut player = guard;
+        // A route that loops on its own has seen every cell once a state repeats
+        while let Some(next) = self.step(player).filter(|next| seen.insert(*next)) {
+            let walked = self.ahead(player) == Some(next.coord);
+            if visited_coords.insert(next.coord)
+                && walked
+                && self.position(next.coord) == Some(Position::Free)
+            {
+                candidates.push((player, next.coord));
             }
-            Position::Obstacle => {
-                self.player.dir = match self.player.dir {
-                    FaceDirection::Up => FaceDirection::Right,
-                    FaceDirection::Right => FaceDirection::Down,
-                    FaceDirection::Down => FaceDirection::Left,
-                    FaceDirection::Left => FaceDirection::Up,
+            player = next;
+        }
+        candidates
+    }
+
+    /// The cell in front of `player`, `None` past the edge of a map without wrap-around
+    fn ahead(&self, player: Player) -> Option<Coord> {
+        let coord = player.coord.step(player.dir);
+        match self.rules.edges {
+            _ if self.space.contains(coord) => Some(coord),
+            Edges::Exit => None,
+            Edges::Wrap => Some(Coord::new(
+                coord.x.rem_euclid(self.space.width() as i32),
+                coord.y.rem_euclid(self.space.height() as i32),
+            )),
+        }
+    }
+
+    /// Where `player` is after one move, `None` once the walk ends
+    pub fn step(&self, player: Player) -> Option<Player> {
+        let next_tile = self.ahead(player)?;
+        let moved = |coord| {
+            Some(Player {
+                coord,
+                dir: player.dir,
+            })
+        };
+        match self.position(next_tile)? {
+            Position::Free => moved(next_tile),
+            Position::OneWay(dir) if dir == player.dir => moved(next_tile),
+            Position::Obstacle | Position::OneWay(_) => Some(Player {
+                coord: player.coord,
+                dir: self.rules.turn.apply(player.dir),
+            }),
+            Position::Teleporter(_) => moved(self.teleports[&next_tile]),
+            Position::Exit => None,
+        }
+    }
+
+    /// The map as in the puzzle text: the trail drawn with `|`, `-` and `+`, the guard as an
+    /// arrow and the given obstacles as `O`
+    fn draw(
+        &self,
+        trail: &HashMap<Coord, Trail>,
+        obstacles: &HashSet<Coord>,
+        guard: Player,
+    ) -> String {
+        let mut picture = String::with_capacity((self.space.width() + 1) * self.space.height());
+        for (coord, position) in self.space.iter() {
+            if coord.x == 0 && coord.y > 0 {
+                picture.push('\n');
+            }
+            picture.push(match (position, trail.get(&coord)) {
+                _ if coord == guard.coord => match guard.dir {
+                    Direction::East => '>',
+                    Direction::South => 'v',
+                    Direction::West => '<',
+                    _ => '^',
+                },
+                _ if obstacles.contains(&coord) => 'O',
+                (Position::Free, Some(Trail { vertical: true, horizontal: true })) => '+',
+                (Position::Free, Some(Trail { vertical: true, .. })) => '|',
+                (Position::Free, Some(Trail { horizontal: true, .. })) => '-',
+                (position, _) => position.glyph(),
+            });
+        }
+        picture
+    }
+}
+
+/// For every free cell and cardinal direction, how many steps the guard walks before an
+/// obstacle stops them, `None` if they walk off the map instead. Only holds for the puzzle's
+/// rules on a map of free cells and obstacles.
+struct JumpTable {
+    width: usize,
+    steps: Vec<[Option<u32>; 4]>,
+}
+
+impl JumpTable {
+    fn new(space: &Grid<Position>) -> Self {
+        let mut table = JumpTable {
+            width: space.width(),
+            steps: vec![[None; 4]; space.width() * space.height()],
+        };
+        for dir in Direction::CARDINAL {
+            let delta = dir.delta();
+            // The cell ahead has to be done before the one behind it
+            let mut coords = space.coords().collect::<Vec<_>>();
+            coords.sort_by_key(|c| -(c.x * delta.x + c.y * delta.y));
+            for coord in coords {
+                let next = coord.step(dir);
+                let steps = match space.get(next) {
+                    None => None,
+                    Some(Position::Free) => table.get(next, dir).map(|s| s + 1),
+                    Some(_) => Some(0),
                 };
-                PlayerMoveResult::Turned(self.player)
+                let index = table.index(coord);
+                table.steps[index][Self::slot(dir)] = steps;
             }
         }
+        table
+    }
+
+    fn index(&self, coord: Coord) -> usize {
+        coord.y as usize * self.width + coord.x as usize
+    }
+
+    /// Cardinal directions are every other compass direction
+    fn slot(dir: Direction) -> usize {
+        dir as usize / 2
+    }
+
+    fn get(&self, coord: Coord, dir: Direction) -> Option<u32> {
+        self.steps[self.index(coord)][Self::slot(dir)]
+    }
+
+    /// Walks `player` straight up to the next obstacle, including the added `obstacle` the
+    /// table doesn't know about, and turns them there. `None` if they leave the map.
+    fn jump(&self, player: Player, obstacle: Option<Coord>) -> Option<Player> {
+        let delta = player.dir.delta();
+        let offset = obstacle.map_or(Coord::new(0, 0), |o| o - player.coord);
+        let distance = offset.x * delta.x + offset.y * delta.y;
+        let ahead = distance > 0 && offset == delta * distance;
+        let steps = match self.get(player.coord, player.dir) {
+            Some(steps) if ahead && distance <= steps as i32 => distance - 1,
+            None if ahead => distance - 1,
+            Some(steps) => steps as i32,
+            None => return None,
+        };
+        Some(Player {
+            coord: player.coord + delta * steps,
+            dir: player.dir.turn_right(),
+        })
     }
 }
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn part1_example() {
-        let day = Day6 {
-            input: "....#.....
+    const EXAMPLE: &str = "....#.....
 .........#
 ..........
 ..#.......
@@ -200,26 +710,316 @@ mod tests {
 .#..^.....
 ........#.
 #.........
-......#..."
-                .to_string(),
+......#...";
+
+    #[test]
+    fn part1_example() {
+        let day = Day6 {
+            input: EXAMPLE.to_string(),
+            jobs: 1,
         };
-        assert_eq!(day.part1().unwrap().trim(), "41");
+        assert_eq!(day.part1().unwrap(), Answer::Integer(41));
     }
     #[test]
     fn part2_example() {
         let day = Day6 {
-            input: "....#.....
-.........#
+            input: EXAMPLE.to_string(),
+            jobs: 1,
+        };
+        assert_eq!(day.part2().unwrap(), Answer::Integer(6));
+    }
+    #[test]
+    fn jumps_match_walking() {
+        let world = World::from(EXAMPLE).unwrap();
+        let jumps = JumpTable::new(&world.space);
+        let candidates = world.candidates(world.guards[0]);
+        assert_eq!(candidates.len(), 40);
+        for (before, obstacle) in candidates {
+            let blocked = world.with_obstacle(obstacle);
+            assert_eq!(
+                blocked.loops(before, Some(&jumps)),
+                blocked.loops(before, None),
+                "obstacle at {:?}",
+                obstacle
+            );
+        }
+    }
+    #[test]
+    fn same_count_on_any_number_of_threads() {
+        let world = World::from(EXAMPLE).unwrap();
+        for jobs in [0, 1, 3, 8, 64] {
+            assert_eq!(world.loop_obstacles(world.guards[0], jobs).len(), 6);
+        }
+    }
+    #[test]
+    fn render_route_and_loop_obstacles() {
+        let day = Day6 {
+            input: EXAMPLE.to_string(),
+            jobs: 1,
+        };
+        let pictures = day.render(3).unwrap();
+        assert_eq!(pictures.len(), 4);
+        assert_eq!(pictures[1].lines().nth(5), Some("....^....."));
+        assert_eq!(pictures[2].lines().nth(6), Some(".#..|....."));
+        assert_eq!(
+            pictures[3],
+            "....#.....
+....+---+#
+....|...|.
+..#.|...|.
+..+-+-+#|.
+..|.|.|.|.
+.#+O^-+-+.
+.+----OO#.
+#O-O--+|..
+......#O.."
+        );
+    }
+    #[test]
+    fn explain_loop_obstacles() {
+        let world = World::from(EXAMPLE).unwrap();
+        let explained = world.explain_loops(world.guards[0], 2);
+        assert_eq!(
+            explained.iter().map(|o| (o.coord.x, o.coord.y)).collect::<Vec<_>>(),
+            vec![(3, 6), (6, 7), (7, 7), (1, 8), (3, 8), (7, 9)]
+        );
+        // Left of the start the guard runs into it on the way back and circles the top right,
+        // 18 cells walked plus 4 turns
+        assert_eq!(
+            explained[0],
+            LoopObstacle {
+                coord: Coord::new(3, 6),
+                length: 22,
+                entry: Player {
+                    coord: Coord::new(4, 6),
+                    dir: Direction::West,
+                },
+            }
+        );
+    }
+    #[test]
+    fn guards_facing_any_direction() {
+        let world = World::from("..#.\n...>\n.<..\nv...").unwrap();
+        assert_eq!(
+            world.guards.iter().map(|g| g.dir).collect::<Vec<_>>(),
+            vec![Direction::East, Direction::West, Direction::South]
+        );
+        let day = Day6 {
+            input: "#...\n.<..".to_string(),
+            jobs: 1,
+        };
+        assert_eq!(day.part1().unwrap(), Answer::Integer(2));
+    }
+
+    #[test]
+    fn map_without_guard() {
+        let day = Day6 {
+            input: "..#.\n....".to_string(),
+            jobs: 1,
+        };
+        assert!(matches!(day.part1(), Err(AocError::MissingInput(_))));
+    }
+
+    #[test]
+    fn several_guards_collide() {
+        let world = World::from(">...<\n.....").unwrap();
+        let patrol = world.patrol();
+        assert_eq!(patrol.visited.iter().map(|v| v.len()).collect::<Vec<_>>(), vec![5, 5]);
+        assert_eq!(
+            patrol.collisions,
+            vec![Collision {
+                tick: 2,
+                coord: Coord::new(2, 0),
+                guards: (0, 1),
+            }]
+        );
+        let day = Day6 {
+            input: ">...<\n.....".to_string(),
+            jobs: 1,
+        };
+        assert_eq!(day.part1().unwrap(), Answer::Integer(5));
+        assert!(matches!(
+            day.part2(),
+            Err(AocError::MalformedLine { day: 6, line: 1, column: 5, .. })
+        ));
+        assert_eq!(
+            day.explain().unwrap().last().unwrap(),
+            "collision of guards 1 and 2 at 2,0 after 2 moves"
+        );
+        // With an even gap the guards never share a cell, they swap
+        let patrol = World::from(">..<\n....").unwrap().patrol();
+        assert_eq!(
+            patrol.collisions,
+            vec![Collision {
+                tick: 2,
+                coord: Coord::new(2, 0),
+                guards: (0, 1),
+            }]
+        );
+    }
+    #[test]
+    fn typed_world_errors() {
+        assert_eq!(
+            World::from("\n  \n").unwrap_err(),
+            WorldError::Grid(GridError::Empty)
+        );
+        assert!(matches!(
+            World::from("....\n..^..\n....").unwrap_err(),
+            WorldError::Grid(GridError::RaggedRow {
+                line: 2,
+                width: 5,
+                expected: 4,
+                ..
+            })
+        ));
+        assert!(matches!(
+            World::from("....\n.^x.").unwrap_err(),
+            WorldError::Grid(GridError::InvalidCell {
+                line: 2,
+                column: 3,
+                cell: 'x',
+                ..
+            })
+        ));
+        assert_eq!(World::from("....\n.#..").unwrap_err(), WorldError::MissingGuard);
+        let world = World::from("\n.^..\n..#.\n<...").unwrap();
+        assert_eq!(
+            world.guard().unwrap_err(),
+            WorldError::DuplicateGuard {
+                first: (2, 2),
+                second: (4, 1),
+            }
+        );
+    }
+    fn visited(world: &World) -> Vec<(i32, i32)> {
+        let mut visited = world.patrol().visited[0]
+            .iter()
+            .map(|c| (c.x, c.y))
+            .collect::<Vec<_>>();
+        visited.sort();
+        visited
+    }
+
+    #[test]
+    fn turn_policies() {
+        let world = World::from(".#..\n.^..\n....").unwrap();
+        assert_eq!(visited(&world), vec![(1, 1), (2, 1), (3, 1)]);
+        let left = world.clone().with_rules(Rules {
+            turn: Turn::Left,
+            ..Rules::default()
+        });
+        assert_eq!(visited(&left), vec![(0, 1), (1, 1)]);
+        let reverse = world.with_rules(Rules {
+            turn: Turn::Reverse,
+            ..Rules::default()
+        });
+        assert_eq!(visited(&reverse), vec![(1, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn wrap_around_edges() {
+        let world = World::from("#...\n.^..\n....").unwrap().with_rules(Rules {
+            edges: Edges::Wrap,
+            ..Rules::default()
+        });
+        assert_eq!(visited(&world), vec![(1, 0), (1, 1), (1, 2)]);
+        let cycle = cycle::hashed(world.guards()[0], |p| world.step(*p)).unwrap();
+        assert_eq!(cycle.length, 3);
+    }
+
+    #[test]
+    fn special_terrain() {
+        // One-way tiles only let the guard in walking their way
+        assert_eq!(
+            visited(&World::from("...\n.S.\n.^.").unwrap()),
+            vec![(1, 2), (2, 2)]
+        );
+        assert_eq!(
+            visited(&World::from("...\n.N.\n.^.").unwrap()),
+            vec![(1, 0), (1, 1), (1, 2)]
+        );
+        // Teleporters keep the direction
+        assert_eq!(
+            visited(&World::from(".1..\n....\n.^.1").unwrap()),
+            vec![(1, 1), (1, 2), (3, 0), (3, 1), (3, 2)]
+        );
+        assert_eq!(
+            World::from(".1..\n.^..").unwrap_err(),
+            WorldError::UnpairedTeleporter {
+                line: 1,
+                column: 2,
+                label: '1',
+            }
+        );
+        // Walls end the walk
+        assert_eq!(
+            visited(&World::from(".X.\n...\n.^.").unwrap()),
+            vec![(1, 1), (1, 2)]
+        );
+    }
+
+    #[test]
+    fn loop_obstacles_under_other_rules() {
+        // Turning left the guard circles counterclockwise, the mirror image of the example
+        let mirrored = "\
+.....#....
+#.........
 ..........
-..#.......
 .......#..
+..#.......
 ..........
-.#..^.....
-........#.
-#.........
-......#..."
-                .to_string(),
+.....^..#.
+.#........
+.........#
+...#......";
+        let world = World::from(mirrored).unwrap().with_rules(Rules {
+            turn: Turn::Left,
+            ..Rules::default()
+        });
+        assert!(!world.is_classic());
+        assert_eq!(world.loop_obstacles(world.guards()[0], 2).len(), 6);
+    }
+    #[test]
+    fn route_that_already_loops() {
+        let input = ".#..\n...#\n#^..\n..#.";
+        let world = World::from(input).unwrap();
+        let candidates = world.candidates(world.guards()[0]);
+        assert_eq!(
+            candidates.iter().map(|(_, c)| (c.x, c.y)).collect::<Vec<_>>(),
+            vec![(1, 1), (2, 1), (2, 2)]
+        );
+        let day = Day6 {
+            input: input.to_string(),
+            jobs: 1,
+        };
+        assert_eq!(day.part1().unwrap(), Answer::Integer(4));
+        // Every obstacle on the loop lets the guard escape it
+        assert_eq!(day.part2().unwrap(), Answer::Integer(0));
+    }
+    #[test]
+    fn loop_obstacles_with_wrap_around() {
+        let wrap = Rules {
+            edges: Edges::Wrap,
+            ..Rules::default()
+        };
+        // Nothing but edges, every cell of the route is a loop either way
+        let world = World::from("#...\n.^..\n....").unwrap().with_rules(wrap);
+        let obstacles = world.loop_obstacles(world.guards()[0], 2);
+        assert_eq!(obstacles, vec![Coord::new(1, 0), Coord::new(1, 2)]);
+        // The wall ends the walk, unless an obstacle turns the guard onto an endless row
+        let world = World::from("....\n.^..\n.X..").unwrap().with_rules(wrap);
+        let obstacles = world.loop_obstacles(world.guards()[0], 2);
+        assert_eq!(obstacles, vec![Coord::new(1, 0)]);
+    }
+    #[test]
+    fn render_route_that_already_loops() {
+        let day = Day6 {
+            input: ".#..\n...#\n#^..\n..#.".to_string(),
+            jobs: 1,
         };
-        assert_eq!(day.part2().unwrap().trim(), "6");
+        let pictures = day.render(100).unwrap();
+        // 7 states around the loop, then the final picture
+        assert_eq!(pictures.len(), 8);
+        assert_eq!(pictures[7], ".#..\n.++#\n#^+.\n..#.");
     }
 }
@@ -1,7 +1,13 @@
-pub mod day0;
-pub mod day1;
-pub mod day2;
-pub mod day3;
-pub mod day4;
-pub mod day5;
-pub mod day6;
+use crate::common::registry::Registry;
+
+/// Declares the day modules and collects their registrations, in order.
+/// Adding a day only requires appending its module name here.
+macro_rules! register_days {
+    ($($day:ident),* $(,)?) => {
+        $(pub mod $day;)*
+
+        pub const REGISTRY: Registry = Registry::new(&[$($day::REGISTRATION),*]);
+    };
+}
+
+register_days!(day0, day1, day2, day3, day4, day5, day6);
@@ -1,55 +1,168 @@
-use crate::common::day::Day;
-use crate::days::day0::Day0;
-use crate::days::day1::Day1;
-use crate::days::day2::Day2;
-use crate::days::day3::Day3;
-use crate::days::day4::Day4;
-use crate::days::day5::Day5;
-use crate::days::day6::Day6;
-use std::fs::File;
-use std::io::Read;
+use advent_of_code_2024::common::answers::{Answers, ANSWERS_FILE};
+use advent_of_code_2024::common::bench;
+use advent_of_code_2024::common::cli::{self, Command, Target};
+use advent_of_code_2024::common::render;
+use advent_of_code_2024::common::report::{self, Format};
+use advent_of_code_2024::common::runner::{self, InputSource, Status};
+use advent_of_code_2024::common::scaffold;
+use advent_of_code_2024::days::REGISTRY;
 use std::path::Path;
+use std::process::ExitCode;
 
-mod common;
-mod days;
+fn main() -> ExitCode {
+    // Banner goes to stderr so machine-readable output on stdout stays parseable
+    eprintln!("Hello, world! Starting the advent");
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    println!("Hello, world! Starting the advent");
+fn run() -> Result<(), Box<dyn std::error::Error>> {
     let args = std::env::args().collect::<Vec<String>>();
-    let parsed_input: Result<(String, String), Box<dyn std::error::Error>> = match args.as_slice() {
-        [_, input_folder_arg, day_arg] => {
-            let input_folder = Path::new(input_folder_arg);
-            if !input_folder.is_dir() {
-                return Err("Input folder does not exist".into());
+    match cli::parse(&args)? {
+        Command::List => {
+            for entry in REGISTRY.iter() {
+                println!("Day {}: {}", entry.number, entry.title);
             }
-
-            let input_file = input_folder.join(format!("{}.input", day_arg));
-            if !input_file.is_file() {
-                return Err("Input file does not exist for the specified day".into());
+        }
+        Command::New { number, title } => {
+            let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+            for file in scaffold::new_day(root, number, title.as_deref())? {
+                println!("Wrote {}", file.display());
             }
-
-            let mut buffer = String::new();
-            File::open(&input_file)?.read_to_string(&mut buffer)?;
-            Ok((day_arg.to_string(), buffer))
         }
-        _ => Err("Usage: <program> <input_folder> <day>".into()),
-    };
+        Command::Explain { source, day, jobs } => {
+            let entry = REGISTRY.lookup(&day)?;
+            let lines = runner::create(entry, source.read(entry.number)?, jobs).explain()?;
+            if lines.is_empty() {
+                return Err(format!("Day {} has nothing to explain", entry.number).into());
+            }
+            for line in lines {
+                println!("{}", line);
+            }
+        }
+        Command::Render {
+            source,
+            day,
+            options,
+            jobs,
+        } => {
+            let entry = REGISTRY.lookup(&day)?;
+            let pictures = runner::create(entry, source.read(entry.number)?, jobs)
+                .render(options.frame_limit())?;
+            if pictures.is_empty() {
+                return Err(format!("Day {} can't be rendered", entry.number).into());
+            }
+            render::play(&mut std::io::stdout().lock(), &pictures, &options)?;
+        }
+        Command::Run {
+            source,
+            target,
+            bench: None,
+            record,
+            format,
+            jobs,
+        } => {
+            let mut results = match (&target, &source) {
+                (Target::All, InputSource::Folder(input_folder)) => {
+                    if !input_folder.is_dir() {
+                        return Err("Input folder does not exist".into());
+                    }
+                    runner::run_all(&REGISTRY, input_folder, jobs)
+                }
+                (Target::All, _) => return Err("Running all days needs an input folder".into()),
+                (Target::Day(day_arg), source) => {
+                    let entry = REGISTRY.lookup(day_arg)?;
+                    runner::run_day(entry, source.read(entry.number)?, jobs)
+                }
+            };
+
+            // Expected answers only exist for the inputs in a folder
+            let answers_file = match &source {
+                InputSource::Folder(input_folder) => Some(input_folder.join(ANSWERS_FILE)),
+                _ if record => return Err("--record needs an input folder".into()),
+                _ => None,
+            };
+            let mut answers = match &answers_file {
+                Some(answers_file) => Answers::load(answers_file)?,
+                None => Answers::default(),
+            };
+            runner::verify(&mut results, &answers);
 
-    let (day, input) = parsed_input?;
+            match (format, &target) {
+                (Format::Json, _) => println!("{}", report::to_json(&results)),
+                (Format::Junit, _) => print!("{}", report::to_junit(&results)),
+                (Format::Text, Target::All) => runner::print_table(&results),
+                (Format::Text, Target::Day(_)) => {
+                    for result in &results {
+                        match (&result.answer, &result.message) {
+                            (Some(answer), _) => println!(
+                                "Result part {}: {} ({:.2?}) {}",
+                                result.part, answer, result.elapsed, result.status
+                            ),
+                            (None, message) => {
+                                return Err(message.clone().unwrap_or_default().into())
+                            }
+                        }
+                    }
+                }
+            }
 
-    let day: Box<dyn Day> = match day.as_str() {
-        "0" => Box::new(Day0 { input }),
-        "1" => Box::new(Day1 { input }),
-        "2" => Box::new(Day2 { input }),
-        "3" => Box::new(Day3 { input }),
-        "4" => Box::new(Day4 { input }),
-        "5" => Box::new(Day5 { input }),
-        "6" => Box::new(Day6 { input }),
-        _ => {
-            return Err(format!("No implementation known for day: {}", day.as_str()).into());
+            if let (true, Some(answers_file)) = (record, &answers_file) {
+                runner::record(&results, &mut answers);
+                answers.save(answers_file)?;
+                eprintln!("Recorded answers to {}", answers_file.display());
+            }
+            // Errors fail the run even when recording, skipped days don't
+            if let Some(failed) = results.iter().find(|r| r.status == Status::Error) {
+                return Err(failed.message.clone().unwrap_or_default().into());
+            } else if let (false, Some(failed)) =
+                (record, results.iter().find(|r| r.status == Status::Fail))
+            {
+                return Err(format!(
+                    "Day {} part {} answer mismatch: {}",
+                    failed.day,
+                    failed.part,
+                    failed.message.as_deref().unwrap_or_default()
+                )
+                .into());
+            }
+        }
+        Command::Run {
+            source,
+            target,
+            bench: Some(options),
+            format,
+            ..
+        } => {
+            if format == Format::Junit {
+                return Err("Benchmarks can't be reported as JUnit".into());
+            }
+            let entries = match &target {
+                Target::All => REGISTRY.iter().collect::<Vec<_>>(),
+                Target::Day(day_arg) => vec![REGISTRY.lookup(day_arg)?],
+            };
+            let mut benches = Vec::new();
+            for entry in entries {
+                let input = match source.read(entry.number) {
+                    Ok(input) => input,
+                    Err(_) if matches!(target, Target::All) => continue,
+                    Err(e) => return Err(e.into()),
+                };
+                benches.push(bench::bench_day(entry, input, &options)?);
+            }
+            match format {
+                Format::Json => {
+                    let json = benches.iter().map(|b| b.to_json()).collect::<Vec<_>>();
+                    println!("[{}]", json.join(","));
+                }
+                _ => benches.iter().for_each(|b| b.print()),
+            }
         }
-    };
-    println!("Result part 1: {}", day.part1()?);
-    println!("Result part 2: {}", day.part2()?);
+    }
     Ok(())
 }